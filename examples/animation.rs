@@ -0,0 +1,78 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example animation
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource)]
+struct GameState {}
+
+fn main() {
+    let mut game = Game::new();
+
+    let balls = [
+        "sprite/rolling/ball_blue.png",
+        "sprite/rolling/ball_blue_alt.png",
+        "sprite/rolling/ball_red.png",
+        "sprite/rolling/ball_red_alt.png",
+    ];
+    game.add_animation(
+        "loop",
+        Animation::from_images(balls).with_frame_duration(0.25),
+    );
+    game.add_animation(
+        "ping_pong",
+        Animation::from_images(balls)
+            .with_frame_duration(0.25)
+            .with_mode(AnimationMode::PingPong),
+    );
+    game.add_animation(
+        "one_shot",
+        Animation::from_images(balls)
+            .with_frame_durations([0.1, 0.2, 0.4, 0.8])
+            .with_mode(AnimationMode::OneShot),
+    );
+
+    for (i, name) in ["loop", "ping_pong", "one_shot"].into_iter().enumerate() {
+        let x = -300.0 + 300.0 * i as f32;
+        let ball = game.add_sprite(name, SpritePreset::RollingBallBlue);
+        ball.translation.x = x;
+        ball.scale = 2.0;
+        ball.play_animation(name);
+        let label = game.add_text(format!("{}_label", name), name);
+        label.translation = Vec2::new(x, -100.0);
+    }
+
+    let msg = game.add_text(
+        "msg",
+        "Press Space to pause the animations. Press R to restart the one-shot animation.",
+    );
+    msg.translation.y = 250.0;
+
+    game.add_logic(game_logic);
+    game.run(GameState {});
+}
+
+fn game_logic(engine: &mut Engine, _: &mut GameState) {
+    if engine.keyboard_state.just_pressed(KeyCode::Space) {
+        for sprite in engine.sprites.values_mut() {
+            sprite.animation_paused = !sprite.animation_paused;
+        }
+    }
+    if engine.keyboard_state.just_pressed(KeyCode::KeyR) {
+        engine
+            .sprites
+            .get_mut("one_shot")
+            .unwrap()
+            .play_animation("one_shot");
+        engine.texts.get_mut("one_shot_label").unwrap().value = "one_shot".into();
+    }
+    for event in engine.animation_events.drain(..) {
+        if let Some(text) = engine
+            .texts
+            .get_mut(&format!("{}_label", event.sprite_label))
+        {
+            text.value = format!("{} finished!", event.animation);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example attach
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource)]
+struct GameState {}
+
+fn main() {
+    let mut game = Game::new();
+
+    let msg = game.add_text(
+        "msg",
+        "Arrow keys drive the car. Space spins the barrel on the roof. C toggles colliders.",
+    );
+    msg.translation.y = 300.0;
+
+    let car = game.add_sprite("car", SpritePreset::RacingCarBlue);
+    car.collision = true;
+
+    // The barrel rides on top of the car, spinning around its own center
+    let barrel = game.add_sprite("barrel", SpritePreset::RacingBarrelRed);
+    barrel.parent = Some("car".into());
+    barrel.layer = 1.0;
+    barrel.scale = 0.5;
+    barrel.collision = true;
+
+    // The name tag follows the car around, always "above" it
+    let name_tag = game.add_text("name_tag", "Player 1");
+    name_tag.parent = Some("car".into());
+    name_tag.translation.y = 50.0;
+    name_tag.font_size = 20.0;
+
+    let cone = game.add_sprite("cone", SpritePreset::RacingConeStraight);
+    cone.translation = Vec2::new(250.0, 0.0);
+    cone.collision = true;
+
+    game.add_logic(game_logic);
+    game.run(GameState {});
+}
+
+fn game_logic(engine: &mut Engine, _: &mut GameState) {
+    let ks = &engine.keyboard_state;
+    let forward =
+        ks.pressed(KeyCode::ArrowUp) as i32 as f32 - ks.pressed(KeyCode::ArrowDown) as i32 as f32;
+    let turn = ks.pressed(KeyCode::ArrowLeft) as i32 as f32
+        - ks.pressed(KeyCode::ArrowRight) as i32 as f32;
+    let spin = ks.pressed(KeyCode::Space);
+    if ks.just_pressed(KeyCode::KeyC) {
+        engine.show_colliders = !engine.show_colliders;
+    }
+
+    // Moving the car moves everything attached to it
+    let car = engine.sprites.get_mut("car").unwrap();
+    car.rotation += turn * 2.0 * engine.delta_f32;
+    car.translation += Vec2::from_angle(car.rotation) * forward * 200.0 * engine.delta_f32;
+
+    // Rotation of an attached sprite is relative to its parent
+    if spin {
+        let barrel = engine.sprites.get_mut("barrel").unwrap();
+        barrel.rotation += 5.0 * engine.delta_f32;
+    }
+
+    // Collisions use the world position of attached sprites
+    for event in engine.collision_events.drain(..) {
+        if event.state.is_begin() {
+            println!("{} hit {}", event.pair.0, event.pair.1);
+        }
+    }
+
+    // Ask the engine where an attached sprite ended up in the world
+    let barrel = engine.sprite_world_transform("barrel").unwrap();
+    let position = engine.add_text("position", "");
+    position.value = format!(
+        "Barrel: ({:.0}, {:.0})",
+        barrel.translation.x, barrel.translation.y
+    );
+    position.translation.y = -300.0;
+}
@@ -20,13 +20,28 @@ use rusty_engine::prelude::*;
 struct GameState {
     circle_radius: f32,
     scale: f32,
+    anchor_index: usize,
 }
 
+// The anchors to cycle through with the `a` key
+const ANCHORS: [Anchor; 9] = [
+    Anchor::Center,
+    Anchor::BottomLeft,
+    Anchor::BottomCenter,
+    Anchor::BottomRight,
+    Anchor::CenterRight,
+    Anchor::TopRight,
+    Anchor::TopCenter,
+    Anchor::TopLeft,
+    Anchor::CenterLeft,
+];
+
 impl Default for GameState {
     fn default() -> Self {
         Self {
             circle_radius: 16.0,
             scale: 1.0,
+            anchor_index: 0,
         }
     }
 }
@@ -95,6 +110,8 @@ fn main() {
     c: Generate a circle collider at the current radius (radius defaults to 16.0)*\n\
     +: Increase the radius by 0.5 and generate a circle collider*\n\
     -: Decrease the radius by 0.5 and generate a circle collider*\n\
+    a: Move the sprite's anchor point (the point it rotates around) to the next spot. Colliders are always relative to the center of the image, no matter where the anchor is.\n\
+    Right Mouse Button: Rotate the sprite around its anchor point.\n\
     w: Write the collider file. NOTE: This will overwrite the existing collider file (if any), so make a backup if you need the old one!\n\
     \n\
     *This command deletes the current collider in memory, but only writing the collider file will affect the collider file on disk."
@@ -149,6 +166,12 @@ fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
     if engine.mouse_state.pressed(MouseButton::Right) {
         sprite.rotation += engine.delta_f32 * 6.0;
     }
+    // Change the anchor
+    if engine.keyboard_state.just_pressed(KeyCode::KeyA) {
+        game_state.anchor_index = (game_state.anchor_index + 1) % ANCHORS.len();
+        sprite.anchor = ANCHORS[game_state.anchor_index];
+        println!("Anchor: {:?}", sprite.anchor);
+    }
     // Delete collider
     if engine.keyboard_state.just_pressed(KeyCode::Delete)
         || engine.keyboard_state.just_pressed(KeyCode::Backspace)
@@ -0,0 +1,65 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example fixed_logic
+
+use rusty_engine::prelude::*;
+
+const GRAVITY: f32 = -900.0;
+const BOUNCE_SPEED: f32 = 700.0;
+
+#[derive(Resource)]
+struct GameState {
+    // Positions as of the last two fixed steps, so we can interpolate in between them
+    previous_y: f32,
+    current_y: f32,
+    velocity: f32,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    let msg = game.add_text(
+        "msg",
+        "The ball's physics run 30 times per second, no matter what the frame rate is.",
+    );
+    msg.translation.y = 300.0;
+
+    let ball = game.add_sprite("ball", SpritePreset::RollingBallBlue);
+    ball.translation.y = -250.0;
+    let _ = game.add_sprite("ghost", SpritePreset::RollingBallRedAlt);
+
+    // Physics run at a fixed rate...
+    game.add_fixed_logic(30.0, physics_logic);
+    // ...while drawing happens every frame
+    game.add_logic(render_logic);
+
+    game.run(GameState {
+        previous_y: -250.0,
+        current_y: -250.0,
+        velocity: BOUNCE_SPEED,
+    });
+}
+
+fn physics_logic(engine: &mut Engine, game_state: &mut GameState) {
+    game_state.previous_y = game_state.current_y;
+    game_state.velocity += GRAVITY * engine.fixed_delta_f32;
+    game_state.current_y += game_state.velocity * engine.fixed_delta_f32;
+    if game_state.current_y < -250.0 {
+        game_state.current_y = -250.0;
+        game_state.velocity = BOUNCE_SPEED;
+    }
+}
+
+fn render_logic(engine: &mut Engine, game_state: &mut GameState) {
+    // The blue ball is smoothly interpolated between the last two fixed steps
+    let alpha = engine.fixed_alpha;
+    let ball = engine.sprites.get_mut("ball").unwrap();
+    ball.translation.x = -100.0;
+    ball.translation.y =
+        game_state.previous_y + (game_state.current_y - game_state.previous_y) * alpha;
+
+    // The red ball jumps from fixed step to fixed step
+    let ghost = engine.sprites.get_mut("ghost").unwrap();
+    ghost.translation.x = 100.0;
+    ghost.translation.y = game_state.current_y;
+}
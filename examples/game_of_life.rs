@@ -0,0 +1,114 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example game_of_life
+
+use rusty_engine::prelude::*;
+
+const WIDTH: u32 = 120;
+const HEIGHT: u32 = 66;
+const CELL_SIZE: f32 = 10.0;
+const ALIVE: Color = Color::srgb(0.3, 0.9, 0.4);
+const DEAD: Color = Color::srgb(0.05, 0.05, 0.1);
+
+#[derive(Resource)]
+struct GameState {
+    cells: Vec<bool>,
+    timer: Timer,
+    running: bool,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    // Start with a scattering of live cells
+    let mut cells = vec![false; (WIDTH * HEIGHT) as usize];
+    for (i, cell) in cells.iter_mut().enumerate() {
+        *cell = (i * 7919 + i / 13) % 11 == 0;
+    }
+
+    // The board is a single sprite showing a pixel image, one pixel per cell
+    let pixels = game.add_pixel_image("board", PixelImage::new(WIDTH, HEIGHT));
+    draw(pixels, &cells);
+    let board = game.add_pixel_sprite("board", "board");
+    board.scale = CELL_SIZE;
+
+    let msg = game.add_text(
+        "msg",
+        "Click to flip cells. Space pauses. R clears the board.",
+    );
+    msg.translation.y = 345.0;
+
+    game.add_logic(game_logic);
+    game.run(GameState {
+        cells,
+        timer: Timer::from_seconds(0.1, TimerMode::Repeating),
+        running: true,
+    });
+}
+
+fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
+    if engine.keyboard_state.just_pressed(KeyCode::Space) {
+        game_state.running = !game_state.running;
+    }
+    let mut changed = false;
+    if engine.keyboard_state.just_pressed(KeyCode::KeyR) {
+        game_state.cells.fill(false);
+        changed = true;
+    }
+
+    // Flip the cell under the mouse
+    if engine.mouse_state.just_pressed(MouseButton::Left)
+        && let Some(location) = engine.mouse_state.location()
+    {
+        let x = (location.x / CELL_SIZE + WIDTH as f32 / 2.0).floor();
+        let y = (HEIGHT as f32 / 2.0 - location.y / CELL_SIZE).floor();
+        if (0.0..WIDTH as f32).contains(&x) && (0.0..HEIGHT as f32).contains(&y) {
+            let index = (y as u32 * WIDTH + x as u32) as usize;
+            game_state.cells[index] = !game_state.cells[index];
+            changed = true;
+        }
+    }
+
+    if game_state.running && game_state.timer.tick(engine.delta).just_finished() {
+        game_state.cells = next_generation(&game_state.cells);
+        changed = true;
+    }
+
+    // Changed pixel images are sent to the GPU at the end of the frame, so only draw the cells
+    // when something changed
+    if changed {
+        draw(
+            engine.pixel_images.get_mut("board").unwrap(),
+            &game_state.cells,
+        );
+    }
+}
+
+fn draw(pixels: &mut PixelImage, cells: &[bool]) {
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let alive = cells[(y * WIDTH + x) as usize];
+            pixels.set_pixel(x, y, if alive { ALIVE } else { DEAD });
+        }
+    }
+}
+
+fn next_generation(cells: &[bool]) -> Vec<bool> {
+    let alive = |x: i32, y: i32| {
+        // The board wraps around at the edges
+        let x = x.rem_euclid(WIDTH as i32) as u32;
+        let y = y.rem_euclid(HEIGHT as i32) as u32;
+        cells[(y * WIDTH + x) as usize]
+    };
+    let mut next = Vec::with_capacity(cells.len());
+    for y in 0..HEIGHT as i32 {
+        for x in 0..WIDTH as i32 {
+            let neighbors = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .filter(|&(dx, dy)| (dx, dy) != (0, 0) && alive(x + dx, y + dy))
+                .count();
+            next.push(matches!((alive(x, y), neighbors), (true, 2) | (_, 3)));
+        }
+    }
+    next
+}
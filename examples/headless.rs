@@ -0,0 +1,59 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example headless
+
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource)]
+struct GameState {
+    frames_colliding: u32,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    let car = game.add_sprite("car", SpritePreset::RacingCarBlue);
+    car.translation = Vec2::new(-200.0, 0.0);
+    car.collision = true;
+    let barrel = game.add_sprite("barrel", SpritePreset::RacingBarrelRed);
+    barrel.collision = true;
+
+    game.add_logic(logic);
+
+    // Run two seconds worth of frames at 60 frames per second, without ever opening a window
+    let (engine, game_state) = game.run_headless(
+        GameState {
+            frames_colliding: 0,
+        },
+        120,
+        Duration::from_secs_f32(1.0 / 60.0),
+    );
+
+    let car = engine.sprites.get("car").unwrap();
+    println!("The car ended up at {}", car.translation);
+    println!(
+        "The car was colliding with the barrel for {} frames",
+        game_state.frames_colliding
+    );
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    // Drive to the right at 200 pixels per second
+    let car = engine.sprites.get_mut("car").unwrap();
+    car.translation.x += 200.0 * engine.delta_f32;
+
+    for event in engine.collision_events.drain(..) {
+        if event.state.is_begin() {
+            println!("Collision began at {:.2}s", engine.time_since_startup_f64);
+        } else {
+            println!("Collision ended at {:.2}s", engine.time_since_startup_f64);
+        }
+    }
+    let car = engine.sprites.get("car").unwrap();
+    let barrel = engine.sprites.get("barrel").unwrap();
+    if Collider::colliding(car, barrel) {
+        game_state.frames_colliding += 1;
+    }
+}
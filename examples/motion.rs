@@ -0,0 +1,70 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example motion
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource)]
+struct GameState;
+
+const THRUST: f32 = 600.0;
+const TURN_SPEED: f32 = 3.0;
+
+fn main() {
+    let mut game = Game::new();
+
+    let msg = game.add_text(
+        "msg",
+        "Up arrow: thrust. Left/Right arrows: turn. The engine does the rest!",
+    );
+    msg.translation.y = 330.0;
+
+    // The car slows down by itself, and has a top speed
+    let car = game.add_sprite("car", SpritePreset::RacingCarBlue);
+    car.drag = 1.0;
+    car.max_speed = Some(500.0);
+    car.layer = 10.0;
+
+    // Barrels drift and spin forever, since they have no drag
+    for i in 0..6 {
+        let barrel = game.add_sprite(format!("barrel{i}"), SpritePreset::RacingBarrelRed);
+        let angle = i as f32;
+        barrel.translation = Vec2::from_angle(angle) * 250.0;
+        barrel.velocity = Vec2::from_angle(angle + 2.0) * 80.0;
+        barrel.angular_velocity = if i % 2 == 0 { 1.0 } else { -2.0 };
+    }
+
+    game.add_logic(game_logic);
+    game.run(GameState);
+}
+
+fn game_logic(engine: &mut Engine, _: &mut GameState) {
+    let mut turn = 0.0;
+    if engine.keyboard_state.pressed(KeyCode::ArrowLeft) {
+        turn += TURN_SPEED;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowRight) {
+        turn -= TURN_SPEED;
+    }
+    let thrust = if engine.keyboard_state.pressed(KeyCode::ArrowUp) {
+        THRUST
+    } else {
+        0.0
+    };
+
+    // Just set how the car should be moving -- no need to touch its translation or rotation
+    let car = engine.sprites.get_mut("car").unwrap();
+    car.angular_velocity = turn;
+    car.acceleration = Vec2::from_angle(car.rotation) * thrust;
+
+    // Wrap everything around the edges of the screen
+    let half_size = engine.window_dimensions * 0.5;
+    for sprite in engine.sprites.values_mut() {
+        if sprite.translation.x.abs() > half_size.x {
+            sprite.translation.x = -sprite.translation.x.signum() * half_size.x;
+        }
+        if sprite.translation.y.abs() > half_size.y {
+            sprite.translation.y = -sprite.translation.y.signum() * half_size.y;
+        }
+    }
+}
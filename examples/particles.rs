@@ -0,0 +1,73 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example particles
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource)]
+struct GameState;
+
+fn main() {
+    let mut game = Game::new();
+
+    let msg = game.add_text(
+        "msg",
+        "Drive with the arrow keys. Click anywhere to set off fireworks!",
+    );
+    msg.translation.y = 300.0;
+
+    let car = game.add_sprite("car", SpritePreset::RacingCarRed);
+    car.layer = 10.0;
+
+    // Exhaust that follows the car around
+    let smoke = game.add_emitter("smoke", ShapeKind::Circle(6.0));
+    smoke.parent = Some("car".into());
+    smoke.translation.x = -60.0;
+    smoke.rotation = LEFT;
+    smoke.spread = 0.6;
+    smoke.rate = 40.0;
+    smoke.speed = 60.0;
+    smoke.speed_variation = 20.0;
+    smoke.lifetime = 1.5;
+    smoke.start_color = Color::srgba(0.6, 0.6, 0.6, 0.7);
+    smoke.end_color = Color::srgba(0.6, 0.6, 0.6, 0.0);
+    smoke.end_scale = 4.0;
+
+    // Particles can be images, too
+    let fireworks = game.add_emitter("fireworks", SpritePreset::RollingBallRed);
+    fireworks.layer = 20.0;
+    fireworks.lifetime = 1.2;
+    fireworks.speed = 300.0;
+    fireworks.speed_variation = 100.0;
+    fireworks.gravity = Vec2::new(0.0, -300.0);
+    fireworks.start_scale = 0.3;
+    fireworks.end_scale = 0.05;
+
+    game.add_logic(game_logic);
+    game.run(GameState);
+}
+
+fn game_logic(engine: &mut Engine, _: &mut GameState) {
+    let delta = engine.delta_f32;
+    let car = engine.sprites.get_mut("car").unwrap();
+    if engine.keyboard_state.pressed(KeyCode::ArrowLeft) {
+        car.rotation += 3.0 * delta;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowRight) {
+        car.rotation -= 3.0 * delta;
+    }
+    let driving = engine.keyboard_state.pressed(KeyCode::ArrowUp);
+    if driving {
+        car.translation += Vec2::from_angle(car.rotation) * 300.0 * delta;
+    }
+    // Puff out more smoke while driving
+    engine.emitters.get_mut("smoke").unwrap().rate = if driving { 120.0 } else { 40.0 };
+
+    if engine.mouse_state.just_pressed(MouseButton::Left)
+        && let Some(location) = engine.mouse_state.location()
+    {
+        let fireworks = engine.emitters.get_mut("fireworks").unwrap();
+        fireworks.translation = location;
+        fireworks.burst(200);
+    }
+}
@@ -0,0 +1,116 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example path
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource)]
+struct GameState;
+
+fn main() {
+    let mut game = Game::new();
+
+    let msg = game.add_text(
+        "msg",
+        "Cars follow paths on their own. Space pauses them. Waypoints pop when a car reaches them.",
+    );
+    msg.translation.y = 330.0;
+
+    // A straight-line patrol that goes back and forth
+    let mut patrol = SpritePath::new(
+        PathKind::Lines,
+        vec![
+            Vec2::new(-550.0, 250.0),
+            Vec2::new(-250.0, 250.0),
+            Vec2::new(-250.0, 50.0),
+            Vec2::new(-550.0, 50.0),
+        ],
+        200.0,
+    );
+    patrol.mode = PathMode::PingPong;
+    add_car(&mut game, "patrol", SpritePreset::RacingCarBlue, patrol);
+
+    // A smooth racetrack that loops forever
+    let mut track = SpritePath::new(
+        PathKind::CatmullRom,
+        vec![
+            Vec2::new(0.0, 200.0),
+            Vec2::new(250.0, 250.0),
+            Vec2::new(500.0, 150.0),
+            Vec2::new(400.0, -50.0),
+            Vec2::new(150.0, 0.0),
+            Vec2::new(50.0, 80.0),
+        ],
+        300.0,
+    );
+    track.mode = PathMode::Loop;
+    add_car(&mut game, "track", SpritePreset::RacingCarRed, track);
+
+    // A swoopy Bezier curve with two control points in between each pair of waypoints
+    let mut swoop = SpritePath::new(
+        PathKind::Bezier,
+        vec![
+            Vec2::new(-500.0, -250.0),
+            Vec2::new(-400.0, 0.0),
+            Vec2::new(-100.0, 0.0),
+            Vec2::new(0.0, -250.0),
+            Vec2::new(100.0, -500.0),
+            Vec2::new(400.0, -100.0),
+            Vec2::new(500.0, -250.0),
+        ],
+        250.0,
+    );
+    swoop.mode = PathMode::PingPong;
+    add_car(&mut game, "swoop", SpritePreset::RacingCarGreen, swoop);
+
+    game.add_logic(game_logic);
+    game.run(GameState);
+}
+
+/// Add a car following `path`, with the path drawn underneath it and a marker on each waypoint
+fn add_car(game: &mut Game<GameState>, label: &str, preset: SpritePreset, mut path: SpritePath) {
+    path.face_direction = true;
+
+    let length = path.length();
+    let points: Vec<Vec2> = (0..=100)
+        .filter_map(|i| path.position_at(length * i as f32 / 100.0))
+        .collect();
+    let line = game.add_shape(format!("{label} path"), ShapeKind::Polyline(points));
+    line.stroke = Some(Color::srgb(0.4, 0.4, 0.4));
+
+    let step = if path.kind == PathKind::Bezier { 3 } else { 1 };
+    for (i, waypoint) in path.points.iter().step_by(step).enumerate() {
+        let marker = game.add_shape(format!("{label} waypoint {i}"), ShapeKind::Circle(10.0));
+        marker.translation = *waypoint;
+        marker.fill = Some(Color::srgb(1.0, 0.9, 0.2));
+        marker.layer = 1.0;
+    }
+
+    let car = game.add_sprite(label, preset);
+    car.scale = 0.5;
+    car.layer = 2.0;
+    car.follow_path(path);
+}
+
+fn game_logic(engine: &mut Engine, _: &mut GameState) {
+    if engine.keyboard_state.just_pressed(KeyCode::Space) {
+        for sprite in engine.sprites.values_mut() {
+            if let Some(path) = sprite.path.as_mut() {
+                path.paused = !path.paused;
+            }
+        }
+    }
+
+    // Shrink the waypoint markers back down to normal size
+    for shape in engine.shapes.values_mut() {
+        shape.scale = (shape.scale - engine.delta_f32 * 2.0).max(1.0);
+    }
+
+    // Pop each waypoint marker as a car reaches it
+    for event in engine.path_events.drain(..) {
+        let label = format!("{} waypoint {}", event.sprite_label, event.waypoint);
+        if let Some(marker) = engine.shapes.get_mut(&label) {
+            marker.scale = 2.0;
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example pause
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource)]
+struct GameState {}
+
+fn main() {
+    let mut game = Game::new();
+
+    let msg = game.add_text(
+        "msg",
+        "Press P to pause, S to toggle slow motion. The pause text keeps spinning in real time.",
+    );
+    msg.translation.y = 300.0;
+
+    let car = game.add_sprite("car", SpritePreset::RacingCarYellow);
+    car.translation.x = -300.0;
+
+    game.audio_manager
+        .play_music(MusicPreset::MysteriousMagic, 0.2);
+
+    game.add_logic(game_logic);
+    game.run(GameState {});
+}
+
+fn game_logic(engine: &mut Engine, _: &mut GameState) {
+    if engine.keyboard_state.just_pressed(KeyCode::KeyP) {
+        engine.paused = !engine.paused;
+        if engine.paused {
+            let _ = engine.add_text("paused", "PAUSED");
+        } else {
+            engine.texts.remove("paused");
+        }
+    }
+    if engine.keyboard_state.just_pressed(KeyCode::KeyS) {
+        engine.time_scale = if engine.time_scale < 1.0 { 1.0 } else { 0.25 };
+    }
+
+    // Game time stops while paused, and slows down in slow motion
+    let car = engine.sprites.get_mut("car").unwrap();
+    car.rotation += engine.delta_f32 * 2.0;
+
+    // Real time keeps going no matter what
+    if let Some(paused) = engine.texts.get_mut("paused") {
+        paused.rotation += engine.real_delta_f32;
+    }
+}
@@ -0,0 +1,69 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example record_replay
+
+use rusty_engine::prelude::*;
+
+const RECORDING_FILE: &str = "record_replay.ron";
+
+#[derive(Resource)]
+struct GameState {}
+
+fn main() {
+    let mut game = Game::new();
+
+    let msg = game.add_text(
+        "msg",
+        "Drive with the arrow keys. R: start/stop recording. P: replay the recording.",
+    );
+    msg.translation.y = 300.0;
+
+    let status = game.add_text("status", "");
+    status.translation.y = -300.0;
+
+    let _ = game.add_sprite("car", SpritePreset::RacingCarBlue);
+
+    game.add_logic(game_logic);
+    game.run(GameState {});
+}
+
+fn game_logic(engine: &mut Engine, _: &mut GameState) {
+    if engine.keyboard_state.just_pressed(KeyCode::KeyR) {
+        if engine.is_recording() {
+            engine.stop_recording();
+        } else {
+            // Start from the center, so the replay starts from the same place
+            engine.sprites.get_mut("car").unwrap().translation = Vec2::ZERO;
+            engine.record_input_to_file(RECORDING_FILE);
+        }
+    }
+    if engine.keyboard_state.just_pressed(KeyCode::KeyP)
+        && !engine.is_recording()
+        && let Ok(recording) = InputRecording::read_from_file(RECORDING_FILE)
+    {
+        engine.sprites.get_mut("car").unwrap().translation = Vec2::ZERO;
+        engine.replay_input(recording);
+    }
+
+    // Drive the car. During a replay, the keyboard state comes from the recording.
+    let delta = engine.delta_f32;
+    let car = engine.sprites.get_mut("car").unwrap();
+    if engine.keyboard_state.pressed(KeyCode::ArrowUp) {
+        car.translation += Vec2::from_angle(car.rotation) * 300.0 * delta;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowLeft) {
+        car.rotation += 3.0 * delta;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowRight) {
+        car.rotation -= 3.0 * delta;
+    }
+
+    let status = if engine.is_recording() {
+        "Recording..."
+    } else if engine.is_replaying() {
+        "Replaying..."
+    } else {
+        ""
+    };
+    engine.texts.get_mut("status").unwrap().value = status.into();
+}
@@ -41,10 +41,25 @@ fn main() {
     let cars_left = game.add_text("cars left", format!("Cars left: {}", game_state.cars_left));
     cars_left.translation = Vec2::new(540.0, -320.0);
 
+    // Sparks that burst out of each car that gets hit
+    let explosion = game.add_emitter("explosion", ShapeKind::Circle(4.0));
+    explosion.layer = 20.0;
+    explosion.lifetime = 0.6;
+    explosion.speed = 250.0;
+    explosion.speed_variation = 150.0;
+    explosion.gravity = Vec2::new(0.0, -400.0);
+    explosion.start_color = Color::srgb(1.0, 0.9, 0.3);
+    explosion.end_color = Color::srgba(0.8, 0.1, 0.0, 0.0);
+    explosion.end_scale = 0.3;
+
     game.add_logic(game_logic);
     game.run(game_state);
 }
 
+// Marbles fly up the screen, and cars drive across it
+const MARBLE_SPEED: f32 = 600.0;
+const CAR_SPEED: f32 = 250.0;
+
 fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
     // Handle marble gun movement
     let player = engine.sprites.get_mut("player").unwrap();
@@ -61,26 +76,12 @@ fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
             marble.translation.y = -275.0;
             marble.layer = 5.0;
             marble.collision = true;
+            marble.velocity.y = MARBLE_SPEED;
+            marble.tags.insert("marble".into());
             engine.audio_manager.play_sfx(SfxPreset::Impact2, 0.4);
         }
     }
 
-    // Move marbles
-    const MARBLE_SPEED: f32 = 600.0;
-    engine
-        .sprites
-        .values_mut()
-        .filter(|sprite| sprite.label.starts_with("marble"))
-        .for_each(|marble| marble.translation.y += MARBLE_SPEED * engine.delta_f32);
-
-    // Move cars across the screen
-    const CAR_SPEED: f32 = 250.0;
-    engine
-        .sprites
-        .values_mut()
-        .filter(|sprite| sprite.label.starts_with("car"))
-        .for_each(|car| car.translation.x += CAR_SPEED * engine.delta_f32);
-
     // Clean up sprites that have gone off the screen
     let mut labels_to_delete = Vec::new();
     for (label, sprite) in engine.sprites.iter() {
@@ -119,6 +120,8 @@ fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
             car.translation.x = -740.0;
             car.translation.y = rand::rng().random_range(-100.0..325.0);
             car.collision = true;
+            car.velocity.x = CAR_SPEED;
+            car.tags.insert("car".into());
         }
     }
 
@@ -127,16 +130,18 @@ fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
         if event.state.is_end() {
             continue;
         }
-        if !event.pair.one_starts_with("marble") {
+        let Some((marble, car)) = event.tagged("marble", "car") else {
             continue;
-        }
+        };
 
-        for label in event.pair {
-            engine.sprites.remove(&label);
-            if label.starts_with("marble") {
-                game_state.marble_labels.push(label);
-            }
-            engine.audio_manager.play_sfx(SfxPreset::Confirmation1, 0.2);
+        if let Some(car) = engine.sprites.remove(car) {
+            let explosion = engine.emitters.get_mut("explosion").unwrap();
+            explosion.translation = car.translation;
+            explosion.burst(60);
+        }
+        if engine.sprites.remove(marble).is_some() {
+            game_state.marble_labels.push(marble.to_string());
         }
+        engine.audio_manager.play_sfx(SfxPreset::Confirmation1, 0.2);
     }
 }
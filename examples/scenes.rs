@@ -0,0 +1,86 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example scenes
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource)]
+struct GameState {
+    score: u32,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    // Added before any scene is active, so it stays around for the whole game
+    let title = game.add_text("title", "Scenes Example");
+    title.translation.y = 300.0;
+
+    game.add_scene(
+        "menu",
+        Scene::new().on_enter(menu_setup).with_logic(menu_logic),
+    );
+    game.add_scene(
+        "play",
+        Scene::new()
+            .on_enter(play_setup)
+            .with_logic(play_logic)
+            .on_exit(play_teardown),
+    );
+    game.add_scene(
+        "game over",
+        Scene::new()
+            .on_enter(game_over_setup)
+            .with_logic(game_over_logic),
+    );
+    game.switch_scene("menu");
+
+    game.run(GameState { score: 0 });
+}
+
+fn menu_setup(engine: &mut Engine, _: &mut GameState) {
+    let _ = engine.add_text("menu", "Press Enter to play");
+}
+
+fn menu_logic(engine: &mut Engine, _: &mut GameState) {
+    if engine.keyboard_state.just_pressed(KeyCode::Enter) {
+        engine.switch_scene("play");
+    }
+}
+
+fn play_setup(engine: &mut Engine, game_state: &mut GameState) {
+    game_state.score = 0;
+    let _ = engine.add_sprite("car", SpritePreset::RacingCarRed);
+    let msg = engine.add_text("instructions", "Press Space to score. Get to 5 to win!");
+    msg.translation.y = -300.0;
+}
+
+fn play_logic(engine: &mut Engine, game_state: &mut GameState) {
+    if engine.keyboard_state.just_pressed(KeyCode::Space) {
+        game_state.score += 1;
+        engine.sprites.get_mut("car").unwrap().rotation += 0.5;
+    }
+    if game_state.score >= 5 {
+        engine.switch_scene("game over");
+    }
+}
+
+fn play_teardown(engine: &mut Engine, _: &mut GameState) {
+    engine.audio_manager.play_sfx(SfxPreset::Jingle1, 0.5);
+}
+
+fn game_over_setup(engine: &mut Engine, game_state: &mut GameState) {
+    let _ = engine.add_text(
+        "game over",
+        format!(
+            "You scored {}! Press Enter to go back to the menu",
+            game_state.score
+        ),
+    );
+}
+
+fn game_over_logic(engine: &mut Engine, _: &mut GameState) {
+    if engine.keyboard_state.just_pressed(KeyCode::Enter) {
+        engine.switch_scene("menu");
+    }
+}
@@ -0,0 +1,100 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example shapes
+
+use rusty_engine::prelude::*;
+use std::f32::consts::TAU;
+
+#[derive(Resource)]
+struct GameState;
+
+const IDLE: Color = Color::srgb(0.3, 0.5, 0.9);
+const TOUCHING: Color = Color::srgb(0.9, 0.3, 0.3);
+
+fn main() {
+    let mut game = Game::new();
+
+    let msg = game.add_text(
+        "msg",
+        "No image files here! Move the circle over the other shapes with the mouse.",
+    );
+    msg.translation.y = 300.0;
+
+    let square = game.add_shape("square", ShapeKind::Rectangle(Vec2::new(150.0, 150.0)));
+    square.translation = Vec2::new(-350.0, 50.0);
+    square.fill = Some(IDLE);
+    square.collision = true;
+
+    let hexagon = game.add_shape(
+        "hexagon",
+        ShapeKind::Polygon(
+            (0..6)
+                .map(|i| Vec2::from_angle(i as f32 * TAU / 6.0) * 90.0)
+                .collect(),
+        ),
+    );
+    hexagon.fill = Some(IDLE);
+    hexagon.stroke = Some(Color::WHITE);
+    hexagon.stroke_width = 4.0;
+    hexagon.collision = true;
+
+    // Shapes can be outlines only
+    let ring = game.add_shape("ring", ShapeKind::Circle(80.0));
+    ring.translation = Vec2::new(350.0, 50.0);
+    ring.fill = None;
+    ring.stroke = Some(IDLE);
+    ring.stroke_width = 8.0;
+    ring.collision = true;
+
+    // Polylines are open lines, which never collide
+    let zigzag = game.add_shape(
+        "zigzag",
+        ShapeKind::Polyline(
+            (0..9)
+                .map(|i| Vec2::new(i as f32 * 100.0 - 400.0, (i % 2) as f32 * 40.0))
+                .collect(),
+        ),
+    );
+    zigzag.translation.y = -220.0;
+    zigzag.stroke_width = 3.0;
+
+    let cursor = game.add_shape("cursor", ShapeKind::Circle(25.0));
+    cursor.layer = 1.0;
+    cursor.collision = true;
+
+    game.add_logic(game_logic);
+    game.run(GameState);
+}
+
+fn game_logic(engine: &mut Engine, _: &mut GameState) {
+    if let Some(location) = engine.mouse_state.location() {
+        engine.shapes.get_mut("cursor").unwrap().translation = location;
+    }
+
+    // Shapes are placed and rotated just like sprites
+    let delta = engine.delta_f32;
+    engine.shapes.get_mut("square").unwrap().rotation += delta;
+    engine.shapes.get_mut("hexagon").unwrap().rotation -= delta * 0.5;
+
+    // Color the shapes red while the cursor touches them
+    for event in engine.collision_events.drain(..) {
+        let color = if event.state.is_begin() {
+            TOUCHING
+        } else {
+            IDLE
+        };
+        for label in event.pair {
+            let Some(shape) = engine.shapes.get_mut(&label) else {
+                continue;
+            };
+            if label == "cursor" {
+                continue;
+            }
+            if shape.fill.is_some() {
+                shape.fill = Some(color);
+            } else {
+                shape.stroke = Some(color);
+            }
+        }
+    }
+}
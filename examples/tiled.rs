@@ -0,0 +1,75 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example tiled
+//!
+//! The level is in `assets/map/arena.tmj`, which you can open and edit with the Tiled editor
+//! (https://www.mapeditor.org/).
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource)]
+struct GameState;
+
+fn main() {
+    let mut game = Game::new();
+
+    // The walls become a tilemap, and the player, barrels, and trigger zones become sprites
+    game.load_tiled_map("map/arena.tmj").unwrap();
+
+    let msg = game.add_text("msg", "Arrow keys drive the car. Find the finish!");
+    msg.translation.y = 340.0;
+    msg.layer = 10.0;
+
+    game.add_logic(game_logic);
+    game.run(GameState);
+}
+
+fn game_logic(engine: &mut Engine, _: &mut GameState) {
+    let delta = engine.delta_f32;
+    let mut turn = 0.0;
+    let mut drive = 0.0;
+    if engine.keyboard_state.pressed(KeyCode::ArrowLeft) {
+        turn += 1.0;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowRight) {
+        turn -= 1.0;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowUp) {
+        drive += 1.0;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowDown) {
+        drive -= 1.0;
+    }
+
+    // The player's speed is a custom property set in Tiled
+    let player = engine.sprites.get_mut("player").unwrap();
+    let speed: f32 = player.properties["speed"].parse().unwrap();
+    player.rotation += turn * 3.0 * delta;
+    let forward = Vec2::from_angle(player.rotation);
+    player.translation += forward * drive * speed * delta;
+
+    let events: Vec<CollisionEvent> = engine.collision_events.drain(..).collect();
+    for event in events {
+        if !event.state.is_begin() || !event.pair.either_equals_to("player") {
+            continue;
+        }
+        let other = if event.pair.0 == "player" {
+            &event.pair.1
+        } else {
+            &event.pair.0
+        };
+        // Trigger zones have a message to show
+        if let Some(message) = engine
+            .sprites
+            .get(other)
+            .and_then(|sprite| sprite.properties.get("message"))
+        {
+            let message = message.clone();
+            engine.texts.get_mut("msg").unwrap().value = message;
+            continue;
+        }
+        // Bounce back from walls and barrels
+        let player = engine.sprites.get_mut("player").unwrap();
+        player.translation -= forward * drive * 20.0;
+    }
+}
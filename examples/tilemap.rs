@@ -0,0 +1,106 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example tilemap
+
+use rusty_engine::prelude::*;
+
+// Tile indices (0 is a wall)
+const HOLE: usize = 1;
+const GOAL: usize = 2;
+
+const MAZE: &str = "
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+    0,  ,  ,  , 0,  ,  ,  , 2, 0
+    0,  , 0,  , 0,  , 0, 0,  , 0
+    0,  , 0,  , 1,  ,  , 0,  , 0
+    0,  , 0, 0, 0, 0,  , 0,  , 0
+    0,  ,  , 1,  ,  ,  , 1,  , 0
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+";
+
+#[derive(Resource)]
+struct GameState {
+    start: Vec2,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    let msg = game.add_text(
+        "msg",
+        "Arrow keys roll the marble. Don't fall in the holes! Click a cell to see what's in it.",
+    );
+    msg.translation.y = 300.0;
+
+    // The whole maze is one tilemap, instead of dozens of sprites
+    let maze = game.add_tilemap("maze", Vec2::new(64.0, 64.0));
+    maze.set_tiles(vec![
+        Tile::new(SpritePreset::RollingBlockSquare, true),
+        Tile::new(SpritePreset::RollingHoleStart, false),
+        Tile::new(SpritePreset::RollingHoleEnd, false),
+    ]);
+    maze.set_grid(Tilemap::grid_from_csv(MAZE).unwrap());
+    let start = maze.cell_to_world(UVec2::new(1, 1));
+
+    let marble = game.add_sprite("marble", SpritePreset::RollingBallBlue);
+    marble.translation = start;
+    marble.layer = 1.0;
+    marble.scale = 0.75;
+    marble.collision = true;
+
+    game.add_logic(game_logic);
+    game.run(GameState { start });
+}
+
+fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
+    // Roll the marble around
+    let mut direction = Vec2::ZERO;
+    if engine.keyboard_state.pressed(KeyCode::ArrowUp) {
+        direction.y += 1.0;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowDown) {
+        direction.y -= 1.0;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowLeft) {
+        direction.x -= 1.0;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowRight) {
+        direction.x += 1.0;
+    }
+    let marble = engine.sprites.get_mut("marble").unwrap();
+    marble.translation += direction.normalize_or_zero() * 150.0 * engine.delta_f32;
+
+    // Bounce back from the walls, which are solid tiles
+    for event in engine.collision_events.drain(..) {
+        if event.state.is_begin() && event.pair.either_equals_to("maze") {
+            marble.translation -= direction.normalize_or_zero() * 20.0;
+        }
+    }
+
+    // Find out which cell the center of the marble is in
+    let maze = engine.tilemaps.get("maze").unwrap();
+    if let Some(cell) = maze.world_to_cell(marble.translation) {
+        match maze.index_at(cell) {
+            Some(HOLE) => marble.translation = game_state.start,
+            Some(GOAL) => {
+                println!("You made it!");
+                marble.translation = game_state.start;
+            }
+            _ => {}
+        }
+    }
+
+    // Tell the user what's in the cell they clicked on
+    if engine.mouse_state.just_pressed(MouseButton::Left)
+        && let Some(location) = engine.mouse_state.location()
+    {
+        match maze.world_to_cell(location) {
+            Some(cell) => match maze.tile_at(cell) {
+                Some(tile) if tile.solid => println!("{cell}: a wall"),
+                Some(_) => println!("{cell}: a hole"),
+                None => println!("{cell}: empty"),
+            },
+            None => println!("Outside of the maze"),
+        }
+    }
+}
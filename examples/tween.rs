@@ -0,0 +1,117 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example tween
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource)]
+struct GameState {
+    car_tween: Option<TweenHandle>,
+}
+
+const EASINGS: [Easing; 6] = [
+    Easing::Linear,
+    Easing::QuadInOut,
+    Easing::BackOut,
+    Easing::ElasticOut,
+    Easing::BounceOut,
+    Easing::ExpoIn,
+];
+
+fn main() {
+    let mut game = Game::new();
+
+    let msg = game.add_text(
+        "msg",
+        "Space: send the car on a trip. Escape: cancel the trip. Click: race the barrels.",
+    );
+    msg.translation.y = 330.0;
+
+    // The car goes on a trip when you press space
+    let car = game.add_sprite("car", SpritePreset::RacingCarRed);
+    car.translation = Vec2::new(-400.0, 200.0);
+
+    // One barrel per easing curve, so you can compare them
+    for (i, easing) in EASINGS.iter().enumerate() {
+        let y = 60.0 - i as f32 * 70.0;
+        let barrel = game.add_sprite(format!("barrel{i}"), SpritePreset::RacingBarrelBlue);
+        barrel.translation = Vec2::new(-400.0, y);
+        let label = game.add_text(format!("easing{i}"), format!("{easing:?}"));
+        label.translation = Vec2::new(-550.0, y);
+        label.font_size = 20.0;
+    }
+
+    // The title gently grows and shrinks forever
+    let title = game.add_text("title", "Tweening!");
+    title.translation.y = 270.0;
+    game.add_tween(
+        Tween::text("title", TweenValue::Scale(1.3), 0.8)
+            .with_easing(Easing::SineInOut)
+            .with_repeat(TweenRepeat::Forever)
+            .with_yoyo(true),
+    );
+
+    game.add_logic(game_logic);
+    game.run(GameState { car_tween: None });
+}
+
+fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
+    // A sequence of changes, with a delay in the middle
+    if engine.keyboard_state.just_pressed(KeyCode::Space) {
+        if let Some(handle) = game_state.car_tween {
+            engine.cancel_tween(handle);
+        }
+        let tween = Tween::sprite("car", TweenValue::Translation(Vec2::new(400.0, 200.0)), 1.5)
+            .with_start(TweenValue::Translation(Vec2::new(-400.0, 200.0)))
+            .with_easing(Easing::CubicInOut)
+            .then(
+                Tween::sprite("car", TweenValue::Rotation(LEFT), 0.5)
+                    .with_start(TweenValue::Rotation(0.0)),
+            )
+            .then(
+                Tween::sprite("car", TweenValue::Color(Color::srgb(0.2, 1.0, 0.2)), 0.5)
+                    .with_start(TweenValue::Color(Color::WHITE))
+                    .with_delay(0.5),
+            )
+            .then(
+                Tween::sprite("car", TweenValue::Opacity(0.0), 1.0)
+                    .with_start(TweenValue::Opacity(1.0)),
+            );
+        game_state.car_tween = Some(engine.add_tween(tween));
+    }
+    if engine.keyboard_state.just_pressed(KeyCode::Escape)
+        && let Some(handle) = game_state.car_tween.take()
+    {
+        engine.cancel_tween(handle);
+    }
+
+    // Put the car back once its trip is over
+    for event in engine.tween_events.drain(..) {
+        if Some(event.handle) == game_state.car_tween {
+            game_state.car_tween = None;
+            let car = engine.sprites.get_mut("car").unwrap();
+            car.translation = Vec2::new(-400.0, 200.0);
+            car.rotation = 0.0;
+            car.color = Color::WHITE;
+            car.opacity = 1.0;
+        }
+    }
+
+    // Race the barrels over and back, each with a different easing curve
+    if engine.mouse_state.just_pressed(MouseButton::Left) {
+        for (i, easing) in EASINGS.iter().enumerate() {
+            let y = 60.0 - i as f32 * 70.0;
+            engine.add_tween(
+                Tween::sprite(
+                    format!("barrel{i}"),
+                    TweenValue::Translation(Vec2::new(400.0, y)),
+                    2.0,
+                )
+                .with_start(TweenValue::Translation(Vec2::new(-400.0, y)))
+                .with_easing(*easing)
+                .with_repeat(TweenRepeat::Times(1))
+                .with_yoyo(true),
+            );
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! Facilities for animating sprites by flipping through a series of images, either from a grid
+//! sprite sheet or from a list of image files.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! // A sprite sheet with 4 columns and 2 rows of 32x32 pixel frames
+//! game.add_animation(
+//!     "explode",
+//!     Animation::from_sprite_sheet("sprite/explosion.png", UVec2::new(32, 32), 4, 2)
+//!         .with_frame_duration(0.05)
+//!         .with_mode(AnimationMode::OneShot),
+//! );
+//! let sprite = game.add_sprite("boom", "sprite/explosion.png");
+//! sprite.play_animation("explode");
+//! game.run(GameState);
+//! # }
+//! ```
+//!
+//! When a one-shot animation finishes, an [`AnimationEvent`] shows up in
+//! [`Engine::animation_events`](crate::prelude::Engine::animation_events).
+
+use crate::{prelude::Engine, sprite::Sprite};
+use bevy::{
+    image::{TextureAtlas, TextureAtlasLayout, TextureAtlasPlugin},
+    platform::collections::{HashMap, HashSet},
+    prelude::{Sprite as BevySprite, *},
+};
+use std::{path::PathBuf, time::Duration};
+
+pub(crate) struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        // Bevy's sprite plugin normally adds this, but there is no sprite plugin when headless
+        if !app.is_plugin_added::<TextureAtlasPlugin>() {
+            app.add_plugins(TextureAtlasPlugin);
+        }
+        app.add_systems(PostUpdate, show_animation_frames);
+    }
+}
+
+/// Where the frames of an [`Animation`] come from
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationFrames {
+    /// A single image containing a grid of equally-sized frames. The frames are numbered from
+    /// left to right, then top to bottom, starting at `0`. `indices` are the frames to show, in
+    /// order.
+    SpriteSheet {
+        filepath: PathBuf,
+        frame_size: UVec2,
+        columns: u32,
+        rows: u32,
+        indices: Vec<usize>,
+    },
+    /// A separate image file for each frame, in order
+    Images(Vec<PathBuf>),
+}
+
+/// What an [`Animation`] does when it reaches its last frame
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnimationMode {
+    /// Start over from the first frame
+    #[default]
+    Loop,
+    /// Play backwards to the first frame, then forwards again, and so on
+    PingPong,
+    /// Stay on the last frame, and generate an [`AnimationEvent`]
+    OneShot,
+}
+
+/// An [`Animation`] is a series of frames that a [`Sprite`] can flip through. Create one with
+/// [`Animation::from_sprite_sheet`] or [`Animation::from_images`], set it up with the builder
+/// methods, and then add it to the game with
+/// [`Engine::add_animation`](crate::prelude::Engine::add_animation).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Animation {
+    /// Where the frames come from
+    pub frames: AnimationFrames,
+    /// How long each frame is shown. Frames without a duration here use `default_frame_duration`.
+    pub frame_durations: Vec<Duration>,
+    /// How long frames are shown if they don't have a duration in `frame_durations`
+    pub default_frame_duration: Duration,
+    /// What happens when the last frame is reached
+    pub mode: AnimationMode,
+}
+
+impl Animation {
+    /// An animation made from a grid sprite sheet: a single image (`filepath` is relative to the
+    /// `assets/` directory) with `columns` columns and `rows` rows of frames, each `frame_size`
+    /// pixels in size. All the frames are shown, left to right and then top to bottom, at 10
+    /// frames per second.
+    pub fn from_sprite_sheet<P: Into<PathBuf>>(
+        filepath: P,
+        frame_size: UVec2,
+        columns: u32,
+        rows: u32,
+    ) -> Self {
+        Self::new(AnimationFrames::SpriteSheet {
+            filepath: filepath.into(),
+            frame_size,
+            columns,
+            rows,
+            indices: (0..(columns * rows) as usize).collect(),
+        })
+    }
+
+    /// An animation made from a separate image file (relative to the `assets/` directory) for
+    /// each frame, shown in order at 10 frames per second.
+    pub fn from_images<I, P>(filepaths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        Self::new(AnimationFrames::Images(
+            filepaths.into_iter().map(Into::into).collect(),
+        ))
+    }
+
+    fn new(frames: AnimationFrames) -> Self {
+        Self {
+            frames,
+            frame_durations: Vec::new(),
+            default_frame_duration: Duration::from_millis(100),
+            mode: AnimationMode::default(),
+        }
+    }
+
+    /// For sprite sheet animations, only show these frames of the sheet, in this order. This lets
+    /// you keep several animations on one sprite sheet. Has no effect on animations made from a
+    /// list of images.
+    pub fn with_sheet_indices<I: IntoIterator<Item = usize>>(mut self, sheet_indices: I) -> Self {
+        if let AnimationFrames::SpriteSheet { indices, .. } = &mut self.frames {
+            *indices = sheet_indices.into_iter().collect();
+        }
+        self
+    }
+
+    /// Show every frame for `seconds` seconds. Negative (or NaN) seconds count as zero, and frames
+    /// that last zero seconds are skipped.
+    pub fn with_frame_duration(mut self, seconds: f32) -> Self {
+        self.frame_durations.clear();
+        self.default_frame_duration = seconds_to_duration(seconds);
+        self
+    }
+
+    /// Show each frame for its own number of seconds. The first duration is for the first frame,
+    /// and so on. Any frames left over use the default duration. As with
+    /// [`with_frame_duration`](Animation::with_frame_duration), negative (or NaN) seconds count as
+    /// zero, and frames that last zero seconds are skipped.
+    pub fn with_frame_durations<I: IntoIterator<Item = f32>>(mut self, seconds: I) -> Self {
+        self.frame_durations = seconds.into_iter().map(seconds_to_duration).collect();
+        self
+    }
+
+    /// What to do when the last frame is reached. The default is [`AnimationMode::Loop`].
+    pub fn with_mode(mut self, mode: AnimationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// The number of frames in the animation
+    pub fn frame_count(&self) -> usize {
+        match &self.frames {
+            AnimationFrames::SpriteSheet { indices, .. } => indices.len(),
+            AnimationFrames::Images(filepaths) => filepaths.len(),
+        }
+    }
+
+    /// How long a frame is shown
+    pub fn frame_duration(&self, frame: usize) -> Duration {
+        self.frame_durations
+            .get(frame)
+            .copied()
+            .unwrap_or(self.default_frame_duration)
+    }
+
+    /// The frame after `frame`, or `None` if a one-shot animation just finished. `reverse` is the
+    /// direction of a ping-pong animation, which gets flipped at each end.
+    fn next_frame(&self, frame: usize, reverse: &mut bool) -> Option<usize> {
+        let frame_count = self.frame_count();
+        if frame_count <= 1 {
+            return (self.mode != AnimationMode::OneShot).then_some(0);
+        }
+        match self.mode {
+            AnimationMode::Loop => Some((frame + 1) % frame_count),
+            AnimationMode::OneShot => (frame + 1 < frame_count).then_some(frame + 1),
+            AnimationMode::PingPong => {
+                if (*reverse && frame == 0) || (!*reverse && frame + 1 >= frame_count) {
+                    *reverse = !*reverse;
+                }
+                Some(if *reverse { frame - 1 } else { frame + 1 })
+            }
+        }
+    }
+}
+
+/// Convert seconds to a duration without panicking on negative, NaN, or enormous values
+fn seconds_to_duration(seconds: f32) -> Duration {
+    // `max` turns NaN into zero
+    Duration::try_from_secs_f32(seconds.max(0.0)).unwrap_or(Duration::MAX)
+}
+
+/// An [`AnimationEvent`] is generated when a sprite's [`AnimationMode::OneShot`] animation shows
+/// its last frame for its full duration. See
+/// [`Engine::animation_events`](crate::prelude::Engine::animation_events).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnimationEvent {
+    /// The label of the sprite whose animation finished
+    pub sprite_label: String,
+    /// The name of the animation that finished
+    pub animation: String,
+}
+
+/// The bookkeeping for a sprite's animation that users don't need to see
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct AnimationState {
+    // the animation that was playing last frame, so we can tell when it changes
+    pub(crate) playing: Option<String>,
+    // how long the current frame has been shown
+    pub(crate) frame_elapsed: Duration,
+    // whether a ping-pong animation is going backwards
+    pub(crate) reverse: bool,
+    // whether a one-shot animation has finished
+    pub(crate) finished: bool,
+}
+
+/// Advance all the sprites' animations by `engine.delta`, generating events for any one-shot
+/// animations that finish. Called each frame before the logic functions run.
+pub(crate) fn advance_animations(engine: &mut Engine) {
+    engine.animation_events.clear();
+    let delta = engine.delta;
+    for sprite in engine.sprites.values_mut() {
+        let Some(name) = sprite.animation.as_ref() else {
+            sprite.animation_state = AnimationState::default();
+            continue;
+        };
+        let Some(animation) = engine.animations.get(name) else {
+            continue;
+        };
+        // The animation was changed by setting the field directly, so start it from the beginning
+        if sprite.animation_state.playing.as_ref() != Some(name) {
+            sprite.animation_state = AnimationState {
+                playing: Some(name.clone()),
+                ..Default::default()
+            };
+            sprite.animation_frame = 0;
+        }
+        if sprite.animation_paused || sprite.animation_state.finished {
+            continue;
+        }
+        let state = &mut sprite.animation_state;
+        state.frame_elapsed = state.frame_elapsed.saturating_add(delta);
+        // Limit how many frames can go by in one update, so an animation whose frames all last zero
+        // seconds can't spin forever
+        let mut frames_left = animation.frame_count().max(1) * 2;
+        loop {
+            sprite.animation_frame = sprite
+                .animation_frame
+                .min(animation.frame_count().saturating_sub(1));
+            let frame_duration = animation.frame_duration(sprite.animation_frame);
+            // Zero-duration frames are skipped straight past
+            if state.frame_elapsed < frame_duration {
+                break;
+            }
+            if frames_left == 0 {
+                // Drop the rest of the time, rather than carrying it over to the next update
+                state.frame_elapsed = Duration::ZERO;
+                break;
+            }
+            frames_left -= 1;
+            state.frame_elapsed -= frame_duration;
+            match animation.next_frame(sprite.animation_frame, &mut state.reverse) {
+                Some(next_frame) => sprite.animation_frame = next_frame,
+                None => {
+                    state.finished = true;
+                    state.frame_elapsed = Duration::ZERO;
+                    engine.animation_events.push(AnimationEvent {
+                        sprite_label: sprite.label.clone(),
+                        animation: name.clone(),
+                    });
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// The size of the current frame of a sprite's animation, if it is a sprite sheet animation
+pub(crate) fn sheet_frame_size(engine: &Engine, sprite: &Sprite) -> Option<Vec2> {
+    let animation = engine.animations.get(sprite.animation.as_ref()?)?;
+    match &animation.frames {
+        AnimationFrames::SpriteSheet { frame_size, .. } => Some(frame_size.as_vec2()),
+        AnimationFrames::Images(_) => None,
+    }
+}
+
+/// system - show the current frame of each animated sprite, and put the normal image back on
+/// sprites that stopped animating
+pub(crate) fn show_animation_frames(
+    engine: Res<Engine>,
+    asset_server: Res<AssetServer>,
+    mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut layout_handles: Local<HashMap<(UVec2, u32, u32), Handle<TextureAtlasLayout>>>,
+    mut animated: Local<HashSet<Entity>>,
+    mut query: Query<(Entity, &Sprite, &mut BevySprite)>,
+) {
+    for (entity, sprite, mut bevy_sprite) in query.iter_mut() {
+        let Some(animation) = sprite
+            .animation
+            .as_ref()
+            .and_then(|name| engine.animations.get(name))
+        else {
+            if animated.remove(&entity) {
+                // A pixel image is put back by the pixel image system, and a sprite without a
+                // file (like a shape imported from a Tiled map) goes back to having no image
+                if sprite.pixel_image.is_none() {
+                    bevy_sprite.image = if sprite.filepath.as_os_str().is_empty() {
+                        Handle::default()
+                    } else {
+                        asset_server.load(sprite.filepath.clone())
+                    };
+                }
+                bevy_sprite.texture_atlas = None;
+            }
+            continue;
+        };
+        animated.insert(entity);
+        match &animation.frames {
+            AnimationFrames::SpriteSheet {
+                filepath,
+                frame_size,
+                columns,
+                rows,
+                indices,
+            } => {
+                let Some(&index) = indices.get(sprite.animation_frame) else {
+                    continue;
+                };
+                let layout = layout_handles
+                    .entry((*frame_size, *columns, *rows))
+                    .or_insert_with(|| {
+                        atlas_layouts.add(TextureAtlasLayout::from_grid(
+                            *frame_size,
+                            *columns,
+                            *rows,
+                            None,
+                            None,
+                        ))
+                    })
+                    .clone();
+                let image = asset_server.load(filepath.clone());
+                if bevy_sprite.image != image {
+                    bevy_sprite.image = image;
+                }
+                let texture_atlas = Some(TextureAtlas { layout, index });
+                if bevy_sprite.texture_atlas != texture_atlas {
+                    bevy_sprite.texture_atlas = texture_atlas;
+                }
+            }
+            AnimationFrames::Images(filepaths) => {
+                let Some(filepath) = filepaths.get(sprite.animation_frame) else {
+                    continue;
+                };
+                let image = asset_server.load(filepath.clone());
+                if bevy_sprite.image != image {
+                    bevy_sprite.image = image;
+                }
+                if bevy_sprite.texture_atlas.is_some() {
+                    bevy_sprite.texture_atlas = None;
+                }
+            }
+        }
+    }
+}
@@ -247,9 +247,28 @@ pub struct Music;
 pub fn queue_managed_audio_system(
     mut commands: Commands,
     music_query: Query<(Entity, &AudioSink), With<Music>>,
+    sink_query: Query<&AudioSink>,
     asset_server: Res<AssetServer>,
     mut game_state: ResMut<Engine>,
+    time: Res<Time<Virtual>>,
+    mut was_paused: Local<bool>,
 ) {
+    // Pause or resume everything that's playing when the game is paused or unpaused
+    let paused = time.is_paused();
+    if paused != *was_paused {
+        for sink in sink_query.iter() {
+            if paused {
+                sink.pause();
+            } else {
+                sink.play();
+            }
+        }
+        *was_paused = paused;
+    }
+    // Leave any queued audio in the queue until the game is unpaused
+    if paused {
+        return;
+    }
     for (sfx, volume) in game_state.audio_manager.sfx_queue.drain(..) {
         commands.spawn((
             AudioPlayer::<AudioSource>(asset_server.load(format!("audio/{}", sfx))),
@@ -285,3 +304,11 @@ pub fn queue_managed_audio_system(
         }
     }
 }
+
+/// The Bevy system used instead of [`queue_managed_audio_system`] when running headless. There is
+/// no audio device to play anything on, so any queued audio is simply discarded.
+#[doc(hidden)]
+pub fn discard_managed_audio_system(mut game_state: ResMut<Engine>) {
+    game_state.audio_manager.sfx_queue.clear();
+    game_state.audio_manager.music_queue.clear();
+}
@@ -0,0 +1,103 @@
+//! The error type for fallible Rusty Engine operations
+//!
+//! Most Rusty Engine methods keep things simple by printing a message and carrying on (or exiting)
+//! when something goes wrong. When you need to handle failures yourself -- in an automated test,
+//! or when embedding Rusty Engine in a larger application -- use the fallible `try_` variants such
+//! as [`Game::try_new`](crate::prelude::Game::try_new),
+//! [`Sprite::try_new`](crate::prelude::Sprite::try_new), and
+//! [`Sprite::try_write_collider`](crate::prelude::Sprite::try_write_collider), which return an
+//! [`EngineError`] instead.
+
+use std::{fmt, io, path::PathBuf};
+
+/// Everything that can go wrong in a fallible Rusty Engine operation
+#[derive(Debug)]
+pub enum EngineError {
+    /// The `assets/` directory could not be found in the current working directory
+    MissingAssetsDirectory,
+    /// A file (for example, a collider file) could not be read or written
+    Io { filepath: PathBuf, error: io::Error },
+    /// A RON file (for example, a collider file) could not be parsed
+    Parse {
+        filepath: PathBuf,
+        error: Box<ron::error::SpannedError>,
+    },
+    /// A value could not be converted to RON
+    Serialize(ron::Error),
+    /// A sprite has no collider, so there is nothing to write
+    NoCollider { label: String },
+    /// A sprite or text with the same label already exists
+    DuplicateLabel(String),
+    /// A value in a tilemap grid is not a tile index. `row` and `column` start at zero.
+    InvalidTile {
+        row: usize,
+        column: usize,
+        value: String,
+    },
+    /// A Tiled map or tileset could not be parsed, or uses a feature that isn't supported
+    Tiled { filepath: PathBuf, message: String },
+    /// The RGBA buffer for a pixel image is the wrong length for the image's size
+    PixelImageSize { expected: usize, actual: usize },
+    /// A logic function takes a different game state type than the game does. Both are type names.
+    GameStateType {
+        expected: &'static str,
+        actual: &'static str,
+    },
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::MissingAssetsDirectory => write!(
+                f,
+                "could not find assets directory. Have you downloaded the assets?\nhttps://github.com/CleanCut/rusty_engine#you-must-download-the-assets-separately"
+            ),
+            EngineError::Io { filepath, error } => {
+                write!(f, "failed accessing {}: {error}", filepath.display())
+            }
+            EngineError::Parse { filepath, error } => {
+                write!(f, "failed parsing {}: {error}", filepath.display())
+            }
+            EngineError::Serialize(error) => write!(f, "failed converting to ron: {}", error),
+            EngineError::NoCollider { label } => {
+                write!(f, "sprite {:?} does not have a collider", label)
+            }
+            EngineError::DuplicateLabel(label) => {
+                write!(f, "the label {:?} is already in use", label)
+            }
+            EngineError::InvalidTile { row, column, value } => write!(
+                f,
+                "{:?} at row {row}, column {column} is not a tile index",
+                value
+            ),
+            EngineError::Tiled { filepath, message } => {
+                write!(f, "failed importing {}: {message}", filepath.display())
+            }
+            EngineError::PixelImageSize { expected, actual } => write!(
+                f,
+                "expected {expected} bytes of RGBA pixel data, but got {actual}"
+            ),
+            EngineError::GameStateType { expected, actual } => write!(
+                f,
+                "the logic function takes a game state of type {actual}, but the game state is a {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EngineError::Io { error, .. } => Some(error),
+            EngineError::Parse { error, .. } => Some(error),
+            EngineError::Serialize(error) => Some(error),
+            EngineError::MissingAssetsDirectory
+            | EngineError::NoCollider { .. }
+            | EngineError::DuplicateLabel(_)
+            | EngineError::InvalidTile { .. }
+            | EngineError::Tiled { .. }
+            | EngineError::PixelImageSize { .. }
+            | EngineError::GameStateType { .. } => None,
+        }
+    }
+}
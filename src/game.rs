@@ -1,26 +1,46 @@
 use bevy::{
     app::AppExit,
+    asset::LoadState,
+    input::InputPlugin,
     platform::collections::HashMap,
     prelude::{Sprite as BevySprite, *},
-    time::Time,
-    window::{PrimaryWindow, WindowPlugin},
+    sprite::Anchor as BevyAnchor,
+    text::TextPlugin,
+    time::{Real, Time, TimeUpdateStrategy, Virtual},
+    window::{ExitCondition, PrimaryWindow, WindowPlugin},
 };
 use bevy_prototype_lyon::prelude::*;
 use std::{
+    any::{Any, TypeId},
     ops::{Deref, DerefMut},
-    path::PathBuf,
+    path::{Path, PathBuf},
     time::Duration,
 };
 
 use crate::{
-    audio::AudioManager,
+    animation::{Animation, AnimationEvent, AnimationPlugin, advance_animations, sheet_frame_size},
+    audio::{AudioManager, discard_managed_audio_system},
+    error::EngineError,
+    headless::HeadlessGame,
+    hierarchy::{WorldTransform, world_sprites, world_text_transforms},
+    input::{SimulatedInput, SimulatedInputPlugin},
     mouse::{CursorMoved, MouseButtonInput, MouseMotion, MousePlugin, MouseWheel},
+    particles::{ParticleEmitter, ParticleLook, ParticlePlugin},
+    path::{PathEvent, advance_paths},
+    physics::{Collider, ExistingCollisions, detect_collisions},
+    pixels::{PixelImage, PixelImagePlugin},
     prelude::{
         AudioManagerPlugin, CollisionEvent, KeyboardInput, KeyboardPlugin, KeyboardState,
         MouseState, PhysicsPlugin,
     },
-    sprite::Sprite,
+    recording::{InputRecorder, InputRecording, RecordingPlugin},
+    scene::{Scene, Scenes},
+    shape::{Shape, ShapeKind, ShapesPlugin},
+    sprite::{Sprite, TagIndex},
     text::Text,
+    tiled::TiledMap,
+    tilemap::{Tilemap, TilemapPlugin},
+    tween::{ActiveTween, Tween, TweenEvent, TweenHandle, advance_tweens},
 };
 
 // Public re-export
@@ -41,7 +61,7 @@ pub use bevy::window::{CursorOptions, Window, WindowMode, WindowResolution};
 /// INFO fields are provided as fresh, readable information to you each frame. Since information in
 /// these fields are overwritten every frame, any changes to them are ignored. Thus, you can feel
 /// free to, e.g. consume all the events out of the `collision_events` vector.
-#[derive(Default, Debug, Resource)]
+#[derive(Debug, Resource)]
 pub struct Engine {
     /// SYNCED - The state of all sprites this frame. To add a sprite, use the
     /// [`add_sprite`](Engine::add_sprite) method. Modify & remove sprites as you like.
@@ -49,6 +69,19 @@ pub struct Engine {
     /// SYNCED - The state of all texts this frame. For convenience adding a text, use the
     /// [`add_text`](Engine::add_text) method. Modify & remove text as you like.
     pub texts: HashMap<String, Text>,
+    /// SYNCED - The state of all tilemaps. To add a tilemap, use the
+    /// [`add_tilemap`](Engine::add_tilemap) method. Modify & remove tilemaps as you like.
+    pub tilemaps: HashMap<String, Tilemap>,
+    /// SYNCED - The state of all shapes. To add a shape, use the [`add_shape`](Engine::add_shape)
+    /// method. Modify & remove shapes as you like.
+    pub shapes: HashMap<String, Shape>,
+    /// SYNCED - The state of all particle emitters. To add an emitter, use the
+    /// [`add_emitter`](Engine::add_emitter) method. Modify & remove emitters as you like.
+    pub emitters: HashMap<String, ParticleEmitter>,
+    /// SYNCED - Images drawn pixel by pixel, by label. To add one, use the
+    /// [`add_pixel_image`](Engine::add_pixel_image) method. Change their pixels & remove them as
+    /// you like.
+    pub pixel_images: HashMap<String, PixelImage>,
     /// SYNCED - If set to `true`, the game exits. Note: the current frame will run to completion first.
     pub should_exit: bool,
     /// SYNCED - If set to `true`, then debug lines are shown depicting sprite colliders
@@ -59,8 +92,22 @@ pub struct Engine {
     /// between sprites, both sprites must have [`Sprite.collision`] set to `true` and both sprites
     /// must have colliders (use the collider example to create a collider for your own images).
     /// Collision events are generated when two sprites' colliders begin or end overlapping in 2D
-    /// space.
+    /// space, or when a sprite's collider begins or ends overlapping the solid tiles of a
+    /// [`Tilemap`].
     pub collision_events: Vec<CollisionEvent>,
+    /// INFO - All the animation events that occurred this frame. An [`AnimationEvent`] is
+    /// generated when a sprite's one-shot animation finishes.
+    pub animation_events: Vec<AnimationEvent>,
+    // all the animations, by name
+    pub(crate) animations: HashMap<String, Animation>,
+    /// INFO - All the tween events that occurred this frame. A [`TweenEvent`] is generated when a
+    /// tween added with [`add_tween`](Engine::add_tween) finishes.
+    pub tween_events: Vec<TweenEvent>,
+    // the tweens that are playing, in the order they were added
+    pub(crate) tweens: Vec<ActiveTween>,
+    /// INFO - All the path events that occurred this frame. A [`PathEvent`] is generated when a
+    /// sprite following a [`SpritePath`](crate::prelude::SpritePath) reaches a waypoint.
+    pub path_events: Vec<PathEvent>,
     /// INFO - The current state of mouse location and buttons. Useful for input handling that only
     /// cares about the final state of the mouse each frame, and not the intermediate states.
     pub mouse_state: MouseState,
@@ -87,12 +134,45 @@ pub struct Engine {
     /// finally released, a single released event is emitted.
     pub keyboard_events: Vec<KeyboardInput>,
     /// INFO - The delta time (time between frames) for the current frame as a [`Duration`], perfect
-    /// for use with [`Timer`](crate::prelude::Timer)s
+    /// for use with [`Timer`](crate::prelude::Timer)s. Scaled by [`time_scale`](Engine::time_scale)
+    /// and zero while [`paused`](Engine::paused).
     pub delta: Duration,
     /// INFO - The delta time (time between frames) for the current frame as an [`f32`], perfect for
     /// use in math with other `f32`'s. A cheap and quick way to approximate smooth movement
     /// (velocity, accelleration, etc.) is to multiply it by `delta_f32`.
     pub delta_f32: f32,
+    /// SYNCED - How fast time passes in the game. `1.0` is normal speed, `0.5` is half speed (slow
+    /// motion), and `2.0` is double speed. Scales `delta`, `delta_f32`, `time_since_startup`, and
+    /// fixed logic functions. Changes take effect next frame.
+    pub time_scale: f32,
+    /// SYNCED - If set to `true`, the game is paused: `delta` and `delta_f32` are zero, fixed
+    /// logic functions don't run, collision events aren't generated, and managed audio is paused
+    /// (audio queued while paused plays once the game is unpaused). Logic functions still run, so
+    /// you can show a menu and unpause the game. Changes take effect next frame.
+    pub paused: bool,
+    /// INFO - The real delta time (time between frames) for the current frame as a [`Duration`],
+    /// which is not affected by [`time_scale`](Engine::time_scale) or
+    /// [`paused`](Engine::paused). Useful for menus that need to keep working while the game is
+    /// paused.
+    pub real_delta: Duration,
+    /// INFO - The real delta time (time between frames) for the current frame as an [`f32`]. See
+    /// [`real_delta`](Engine::real_delta).
+    pub real_delta_f32: f32,
+    /// INFO - Inside a fixed logic function (see [`Game::add_fixed_logic`]), the fixed delta time
+    /// (time between fixed steps) as a [`Duration`]. Always the same for a given fixed logic
+    /// function.
+    pub fixed_delta: Duration,
+    /// INFO - Inside a fixed logic function (see [`Game::add_fixed_logic`]), the fixed delta time
+    /// (time between fixed steps) as an [`f32`]. Use this instead of `delta_f32` in fixed logic
+    /// functions.
+    pub fixed_delta_f32: f32,
+    /// INFO - How far the game is between the last fixed step and the next one, from `0.0` to
+    /// `1.0`. Useful for smoothly interpolating things that only move in fixed logic functions. If
+    /// you have fixed logic functions with different rates, this is for the first one (use
+    /// [`fixed_alpha_for`](Engine::fixed_alpha_for) for the others).
+    pub fixed_alpha: f32,
+    // the alpha for each fixed logic function
+    fixed_alphas: HashMap<LogicHandle, f32>,
     /// INFO - The amount of time the game has been running since startup as a [`Duration`]
     pub time_since_startup: Duration,
     /// INFO - The amount of time the game has been running as an [`f64`]. This needs to be an f64,
@@ -101,33 +181,377 @@ pub struct Engine {
     pub time_since_startup_f64: f64,
     /// A struct with methods to play sound effects and music
     pub audio_manager: AudioManager,
+    /// A struct with methods to simulate keyboard and mouse input. Simulated input shows up in
+    /// the input fields of the `Engine` on the next frame, exactly as if it were real input.
+    pub simulated_input: SimulatedInput,
+    // whether the images for all the sprites have finished loading
+    all_sprites_loaded: bool,
     /// INFO - Window dimensions in logical pixels. On high DPI screens, there will often be four
     /// physical pixels per logical pixel. On low DPI screens, one logical pixel is one physical
     /// pixel.
     pub window_dimensions: Vec2,
+    /// INFO - The name of the scene that is currently active, if any. See
+    /// [`Game::add_scene`] and [`switch_scene`](Engine::switch_scene).
+    pub current_scene: Option<String>,
+    // the scene to switch to at the end of the frame
+    pub(crate) requested_scene: Option<String>,
+    // changes to the logic functions, waiting to be applied at the end of the frame
+    logic_commands: Vec<LogicCommand>,
+    // the type of the game state, so logic functions for some other type can be turned away
+    game_state_type: Option<(TypeId, &'static str)>,
+    // so we can hand out unique logic handles
+    last_logic_handle: u64,
+    // so we can generate unique sprite labels
+    last_auto_label: u64,
+    // so we can look up sprites by tag
+    tag_index: TagIndex,
+    // so we can hand out unique tween handles
+    last_tween_handle: u64,
+    // whether input is being recorded or replayed
+    pub(crate) input_recorder: InputRecorder,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self {
+            sprites: HashMap::default(),
+            texts: HashMap::default(),
+            tilemaps: HashMap::default(),
+            shapes: HashMap::default(),
+            emitters: HashMap::default(),
+            pixel_images: HashMap::default(),
+            should_exit: false,
+            show_colliders: false,
+            last_show_colliders: false,
+            collision_events: Vec::new(),
+            animation_events: Vec::new(),
+            animations: HashMap::default(),
+            tween_events: Vec::new(),
+            tweens: Vec::new(),
+            path_events: Vec::new(),
+            mouse_state: MouseState::default(),
+            mouse_button_events: Vec::new(),
+            mouse_location_events: Vec::new(),
+            mouse_motion_events: Vec::new(),
+            mouse_wheel_events: Vec::new(),
+            keyboard_state: KeyboardState::default(),
+            keyboard_events: Vec::new(),
+            delta: Duration::default(),
+            delta_f32: 0.0,
+            time_scale: 1.0,
+            paused: false,
+            real_delta: Duration::default(),
+            real_delta_f32: 0.0,
+            fixed_delta: Duration::default(),
+            fixed_delta_f32: 0.0,
+            fixed_alpha: 0.0,
+            fixed_alphas: HashMap::default(),
+            time_since_startup: Duration::default(),
+            time_since_startup_f64: 0.0,
+            audio_manager: AudioManager::default(),
+            simulated_input: SimulatedInput::default(),
+            window_dimensions: Vec2::default(),
+            current_scene: None,
+            requested_scene: None,
+            logic_commands: Vec::new(),
+            game_state_type: None,
+            last_logic_handle: 0,
+            last_auto_label: 0,
+            tag_index: TagIndex::default(),
+            last_tween_handle: 0,
+            all_sprites_loaded: false,
+            input_recorder: InputRecorder::default(),
+        }
+    }
 }
 
 impl Engine {
     #[must_use]
     /// Create and add a [`Sprite`] to the game. Use the `&mut Sprite` that is returned to adjust
-    /// the translation, rotation, etc. Use a *unique* label for each sprite. Attempting to add two
-    /// sprites with the same label will cause a crash.
+    /// the translation, rotation, etc. Use a *unique* label for each sprite. Adding a sprite with
+    /// the same label as an existing sprite replaces the existing sprite. Use
+    /// [`try_add_sprite`](Engine::try_add_sprite) if you would rather get an error, or
+    /// [`add_sprite_auto`](Engine::add_sprite_auto) to have a unique label generated for you.
     pub fn add_sprite<T: Into<String>, P: Into<PathBuf>>(
         &mut self,
         label: T,
         file_or_preset: P,
     ) -> &mut Sprite {
         let label = label.into();
-        self.sprites
-            .insert(label.clone(), Sprite::new(label.clone(), file_or_preset));
+        let sprite = Sprite::new(label.clone(), file_or_preset);
+        self.insert_sprite(sprite)
+    }
+
+    /// Same as [`add_sprite`](Engine::add_sprite), except that an [`EngineError`] is returned if a
+    /// sprite with the same label already exists, or if the sprite's collider file can't be read.
+    pub fn try_add_sprite<T: Into<String>, P: Into<PathBuf>>(
+        &mut self,
+        label: T,
+        file_or_preset: P,
+    ) -> Result<&mut Sprite, EngineError> {
+        let label = label.into();
+        if self.sprites.contains_key(&label) {
+            return Err(EngineError::DuplicateLabel(label));
+        }
+        let sprite = Sprite::try_new(label, file_or_preset)?;
+        Ok(self.insert_sprite(sprite))
+    }
+
+    /// Create and add a [`Sprite`] with a generated label that is guaranteed to be unique: `prefix`
+    /// followed by a number, such as `bullet17`. Returns the generated label along with the
+    /// `&mut Sprite`. This is handy for things like bullets, where you need lots of sprites that
+    /// all act the same way. Give them a tag (see [`Sprite::tags`]) to find them all again with
+    /// [`sprites_with_tag`](Engine::sprites_with_tag).
+    pub fn add_sprite_auto<T: Into<String>, P: Into<PathBuf>>(
+        &mut self,
+        prefix: T,
+        file_or_preset: P,
+    ) -> (String, &mut Sprite) {
+        let prefix = prefix.into();
+        let label = loop {
+            self.last_auto_label += 1;
+            let label = format!("{}{}", prefix, self.last_auto_label);
+            if !self.sprites.contains_key(&label) {
+                break label;
+            }
+        };
+        let sprite = Sprite::new(label.clone(), file_or_preset);
+        (label, self.insert_sprite(sprite))
+    }
+
+    /// Add an [`Animation`] named `name`, which sprites can play by calling
+    /// [`Sprite::play_animation`] or setting [`Sprite::animation`]. Adding an animation with the
+    /// same name as an existing animation replaces it.
+    pub fn add_animation<T: Into<String>>(&mut self, name: T, animation: Animation) {
+        self.animations.insert(name.into(), animation);
+    }
+
+    /// Start playing a [`Tween`], which smoothly changes a field of a sprite or text over time.
+    /// Returns a [`TweenHandle`] which you can pass to [`cancel_tween`](Engine::cancel_tween), and
+    /// which shows up in [`tween_events`](Engine::tween_events) when the tween finishes. Any
+    /// number of tweens can play at once.
+    pub fn add_tween(&mut self, tween: Tween) -> TweenHandle {
+        self.last_tween_handle += 1;
+        let handle = TweenHandle(self.last_tween_handle);
+        self.tweens.push(ActiveTween::new(handle, tween));
+        handle
+    }
+
+    /// Stop a tween, leaving the field it was changing where it is. Returns `false` if the tween
+    /// already finished or was cancelled.
+    pub fn cancel_tween(&mut self, handle: TweenHandle) -> bool {
+        let count = self.tweens.len();
+        self.tweens.retain(|active| active.handle != handle);
+        self.tweens.len() != count
+    }
+
+    /// Whether a tween is still playing
+    pub fn tween_playing(&self, handle: TweenHandle) -> bool {
+        self.tweens.iter().any(|active| active.handle == handle)
+    }
+
+    #[must_use]
+    /// Create and add an empty [`Tilemap`] with cells of the given size to the game. Use the
+    /// `&mut Tilemap` that is returned to add tiles and fill in the grid. If a tilemap with the same
+    /// label already exists, it is replaced.
+    pub fn add_tilemap<T: Into<String>>(&mut self, label: T, tile_size: Vec2) -> &mut Tilemap {
+        let label = label.into();
+        let mut tilemap = Tilemap::new(label.clone(), tile_size);
+        tilemap.scene = self.current_scene.clone();
+        self.tilemaps.insert(label.clone(), tilemap);
+        // Unwrap: Can't crash because we just inserted the tilemap
+        self.tilemaps.get_mut(&label).unwrap()
+    }
+
+    #[must_use]
+    /// Create and add a [`Shape`] to the game. Use the `&mut Shape` that is returned to adjust its
+    /// colors, translation, etc. If a shape with the same label already exists, it is replaced.
+    pub fn add_shape<T: Into<String>>(&mut self, label: T, kind: ShapeKind) -> &mut Shape {
+        let label = label.into();
+        let mut shape = Shape::new(label.clone(), kind);
+        shape.scene = self.current_scene.clone();
+        self.shapes.insert(label.clone(), shape);
+        // Unwrap: Can't crash because we just inserted the shape
+        self.shapes.get_mut(&label).unwrap()
+    }
+
+    #[must_use]
+    /// Create and add a [`ParticleEmitter`] to the game. Particles look like `look`, which can be
+    /// a [`SpritePreset`](crate::prelude::SpritePreset), an image file path, or a [`ShapeKind`].
+    /// Use the `&mut ParticleEmitter` that is returned to set its rate, colors, etc. If an emitter
+    /// with the same label already exists, it is replaced.
+    pub fn add_emitter<T: Into<String>, L: Into<ParticleLook>>(
+        &mut self,
+        label: T,
+        look: L,
+    ) -> &mut ParticleEmitter {
+        let label = label.into();
+        let mut emitter = ParticleEmitter::new(label.clone(), look);
+        emitter.scene = self.current_scene.clone();
+        self.emitters.insert(label.clone(), emitter);
+        // Unwrap: Can't crash because we just inserted the emitter
+        self.emitters.get_mut(&label).unwrap()
+    }
+
+    #[must_use]
+    /// Add a [`PixelImage`] to the game, so that sprites can show it. Use the `&mut PixelImage`
+    /// that is returned to draw on it. If a pixel image with the same label already exists, it is
+    /// replaced.
+    pub fn add_pixel_image<T: Into<String>>(
+        &mut self,
+        label: T,
+        pixel_image: PixelImage,
+    ) -> &mut PixelImage {
+        let label = label.into();
+        self.pixel_images.insert(label.clone(), pixel_image);
+        // Unwrap: Can't crash because we just inserted the pixel image
+        self.pixel_images.get_mut(&label).unwrap()
+    }
+
+    #[must_use]
+    /// Create and add a sprite that shows the [`PixelImage`] with the label `pixel_image` (see
+    /// [`add_pixel_image`](Engine::add_pixel_image)). The sprite has no collider. Use the
+    /// `&mut Sprite` that is returned to adjust the translation, rotation, etc. If a sprite with
+    /// the same label already exists, it is replaced.
+    pub fn add_pixel_sprite<T: Into<String>, P: Into<String>>(
+        &mut self,
+        label: T,
+        pixel_image: P,
+    ) -> &mut Sprite {
+        let mut sprite = Sprite::with_collider(label.into(), PathBuf::new(), Collider::NoCollider);
+        sprite.pixel_image = Some(pixel_image.into());
+        self.insert_sprite(sprite)
+    }
+
+    /// Add the tilemaps and sprites of a [`TiledMap`] to the game. Tilemaps and sprites with the
+    /// same labels as existing ones replace them.
+    pub fn add_tiled_map(&mut self, tiled_map: TiledMap) {
+        for mut tilemap in tiled_map.tilemaps {
+            tilemap.scene = self.current_scene.clone();
+            self.tilemaps.insert(tilemap.label.clone(), tilemap);
+        }
+        for sprite in tiled_map.sprites {
+            let _ = self.insert_sprite(sprite);
+        }
+    }
+
+    /// Read a map made with the [Tiled](https://www.mapeditor.org/) editor from a file inside the
+    /// `assets/` directory, and add its tilemaps and sprites to the game. See
+    /// [`TiledMap::read_from_file`] and [`add_tiled_map`](Engine::add_tiled_map).
+    pub fn load_tiled_map<P: AsRef<Path>>(&mut self, filepath: P) -> Result<(), EngineError> {
+        let tiled_map = TiledMap::read_from_file(filepath)?;
+        self.add_tiled_map(tiled_map);
+        Ok(())
+    }
+
+    /// Whether the images for all the sprites that existed at the start of this frame have
+    /// finished loading (or failed to load), so that their
+    /// [`image_size`](crate::prelude::Sprite::image_size) is known. Images load in the background,
+    /// so this is usually `false` for the first few frames after sprites are added. Images are
+    /// never loaded when running headless (see [`Game::headless`]), so this is always `false` then
+    /// if there are any sprites.
+    pub fn all_sprites_loaded(&self) -> bool {
+        self.all_sprites_loaded
+    }
+
+    /// Where the sprite with the given label is in world space, taking its
+    /// [`parent`](crate::prelude::Sprite::parent) (if any) into account. Returns `None` if there
+    /// is no sprite with that label.
+    pub fn sprite_world_transform(&self, label: &str) -> Option<WorldTransform> {
+        let sprite = self.sprites.get(label)?;
+        Some(
+            WorldTransform::local(sprite)
+                .resolve(sprite.parent.as_deref(), |label| self.sprites.get(label)),
+        )
+    }
+
+    /// Where the text with the given label is in world space, taking its
+    /// [`parent`](crate::prelude::Text::parent) (if any) into account. Returns `None` if there is
+    /// no text with that label.
+    pub fn text_world_transform(&self, label: &str) -> Option<WorldTransform> {
+        let text = self.texts.get(label)?;
+        Some(
+            WorldTransform::local_text(text)
+                .resolve(text.parent.as_deref(), |label| self.sprites.get(label)),
+        )
+    }
+
+    /// The sprites which have the tag (see [`Sprite::tags`]), in no particular order. This uses an
+    /// index, so it doesn't need to check every sprite.
+    pub fn sprites_with_tag<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a Sprite> + 'a {
+        self.tag_index
+            .labels(tag, &self.sprites)
+            .filter_map(|label| self.sprites.get(label))
+    }
+
+    /// The labels of the sprites which have the tag (see [`Sprite::tags`]), in no particular
+    /// order
+    pub fn sprite_labels_with_tag(&self, tag: &str) -> Vec<String> {
+        self.tag_index.labels(tag, &self.sprites).cloned().collect()
+    }
+
+    /// Call `f` with each sprite which has the tag (see [`Sprite::tags`]), so you can change them.
+    /// For example, to move all the bullets:
+    ///
+    /// ```rust,no_run
+    /// # use rusty_engine::prelude::*;
+    /// # fn logic(engine: &mut Engine) {
+    /// let distance = 500.0 * engine.delta_f32;
+    /// engine.for_each_sprite_with_tag("bullet", |bullet| bullet.translation.y += distance);
+    /// # }
+    /// ```
+    pub fn for_each_sprite_with_tag<F: FnMut(&mut Sprite)>(&mut self, tag: &str, mut f: F) {
+        for label in self.sprite_labels_with_tag(tag) {
+            if let Some(sprite) = self.sprites.get_mut(&label) {
+                f(sprite);
+            }
+        }
+    }
+
+    /// Remove all the sprites which have the tag (see [`Sprite::tags`]), and return them
+    pub fn remove_sprites_with_tag(&mut self, tag: &str) -> Vec<Sprite> {
+        self.sprite_labels_with_tag(tag)
+            .iter()
+            .filter_map(|label| self.sprites.remove(label))
+            .collect()
+    }
+
+    /// Add a tag to the sprite with the given label, so that it shows up in tag lookups right
+    /// away. Returns `false` if there is no sprite with that label.
+    pub fn tag_sprite(&mut self, label: &str, tag: &str) -> bool {
+        let Some(sprite) = self.sprites.get_mut(label) else {
+            return false;
+        };
+        sprite.tags.insert(tag.to_string());
+        self.tag_index.insert(label, tag);
+        true
+    }
+
+    /// Remove a tag from the sprite with the given label. Returns `false` if there is no sprite
+    /// with that label.
+    pub fn untag_sprite(&mut self, label: &str, tag: &str) -> bool {
+        let Some(sprite) = self.sprites.get_mut(label) else {
+            return false;
+        };
+        sprite.tags.remove(tag);
+        self.tag_index.remove(label, tag);
+        true
+    }
+
+    fn insert_sprite(&mut self, mut sprite: Sprite) -> &mut Sprite {
+        let label = sprite.label.clone();
+        sprite.scene = self.current_scene.clone();
+        self.tag_index.add(&label);
+        self.sprites.insert(label.clone(), sprite);
         // Unwrap: Can't crash because we just inserted the sprite
         self.sprites.get_mut(&label).unwrap()
     }
 
     #[must_use]
     /// Create and add a [`Text`] to the game. Use the `&mut Text` that is returned to adjust the
-    /// translation, rotation, etc. Use a *unique* label for each text. Attempting to add two texts
-    /// with the same label will cause a crash.
+    /// translation, rotation, etc. Use a *unique* label for each text. Adding a text with the same
+    /// label as an existing text replaces the existing text. Use
+    /// [`try_add_text`](Engine::try_add_text) if you would rather get an error.
     pub fn add_text<T, S>(&mut self, label: T, text: S) -> &mut Text
     where
         T: Into<String>,
@@ -138,74 +562,329 @@ impl Engine {
         let curr_text = Text {
             label: label.clone(),
             value: text,
+            scene: self.current_scene.clone(),
             ..Default::default()
         };
         self.texts.insert(label.clone(), curr_text);
         // Unwrap: Can't crash because we just inserted the text
         self.texts.get_mut(&label).unwrap()
     }
+
+    /// Same as [`add_text`](Engine::add_text), except that an [`EngineError`] is returned if a
+    /// text with the same label already exists.
+    pub fn try_add_text<T, S>(&mut self, label: T, text: S) -> Result<&mut Text, EngineError>
+    where
+        T: Into<String>,
+        S: Into<String>,
+    {
+        let label = label.into();
+        if self.texts.contains_key(&label) {
+            return Err(EngineError::DuplicateLabel(label));
+        }
+        Ok(self.add_text(label, text))
+    }
+
+    /// Switch to the scene named `name` at the end of the current frame. The current scene's exit
+    /// function runs, its sprites and texts are removed (unless they are `persistent`), and then
+    /// the new scene's enter function runs. The new scene's logic functions start running next
+    /// frame. See [`Game::add_scene`].
+    pub fn switch_scene<T: Into<String>>(&mut self, name: T) {
+        self.requested_scene = Some(name.into());
+    }
+
+    /// Add a logic function (or closure) while the game is running. It will start running next
+    /// frame, after any logic functions with the same priority. Returns a [`LogicHandle`] which you
+    /// can use to remove, disable, or enable the logic function later, or
+    /// [`EngineError::GameStateType`] if the logic function takes a different game state type than
+    /// the game does. See [`Game::add_logic`] for the details of what a logic function looks like.
+    pub fn add_logic<S, F>(&mut self, logic_function: F) -> Result<LogicHandle, EngineError>
+    where
+        S: Resource + Send + Sync + 'static,
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        self.add_logic_with_priority(logic_function, 0)
+    }
+
+    /// Same as [`add_logic`](Engine::add_logic), but with a priority. Logic functions run in order
+    /// of priority, lowest first. Logic functions with the same priority run in the order they were
+    /// added. The default priority is `0`.
+    pub fn add_logic_with_priority<S, F>(
+        &mut self,
+        logic_function: F,
+        priority: i32,
+    ) -> Result<LogicHandle, EngineError>
+    where
+        S: Resource + Send + Sync + 'static,
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        self.check_game_state_type::<S>()?;
+        let logic_function =
+            LogicFunction::<S>::new(self.next_logic_handle(), priority, logic_function);
+        let handle = logic_function.handle;
+        self.logic_commands
+            .push(LogicCommand::Add(Box::new(logic_function)));
+        Ok(handle)
+    }
+
+    /// Add a fixed logic function (or closure) while the game is running. Returns
+    /// [`EngineError::GameStateType`] if the logic function takes a different game state type than
+    /// the game does. See [`Game::add_fixed_logic`] for how fixed logic functions work.
+    pub fn add_fixed_logic<S, F>(
+        &mut self,
+        hz: f32,
+        logic_function: F,
+    ) -> Result<LogicHandle, EngineError>
+    where
+        S: Resource + Send + Sync + 'static,
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        self.check_game_state_type::<S>()?;
+        let logic_function = LogicFunction::<S>::new(self.next_logic_handle(), 0, logic_function)
+            .with_fixed_timestep(hz);
+        let handle = logic_function.handle;
+        self.logic_commands
+            .push(LogicCommand::Add(Box::new(logic_function)));
+        Ok(handle)
+    }
+
+    /// Make sure a logic function taking an `S` can run in this game
+    fn check_game_state_type<S: 'static>(&self) -> Result<(), EngineError> {
+        match self.game_state_type {
+            Some((type_id, expected)) if type_id != TypeId::of::<S>() => {
+                Err(EngineError::GameStateType {
+                    expected,
+                    actual: std::any::type_name::<S>(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The interpolation alpha for a particular fixed logic function. See
+    /// [`fixed_alpha`](Engine::fixed_alpha). Returns `None` if there is no fixed logic function
+    /// with that handle.
+    pub fn fixed_alpha_for(&self, handle: LogicHandle) -> Option<f32> {
+        self.fixed_alphas.get(&handle).copied()
+    }
+
+    /// Remove a logic function. It will not run again, starting next frame.
+    pub fn remove_logic(&mut self, handle: LogicHandle) {
+        self.logic_commands.push(LogicCommand::Remove(handle));
+    }
+
+    /// Disable a logic function. It will be skipped, starting next frame, until it is enabled again
+    /// with [`enable_logic`](Engine::enable_logic).
+    pub fn disable_logic(&mut self, handle: LogicHandle) {
+        self.logic_commands.push(LogicCommand::Disable(handle));
+    }
+
+    /// Enable a logic function which was disabled with [`disable_logic`](Engine::disable_logic).
+    /// It will run again, starting next frame.
+    pub fn enable_logic(&mut self, handle: LogicHandle) {
+        self.logic_commands.push(LogicCommand::Enable(handle));
+    }
+
+    /// Start recording the keyboard and mouse input (and `delta`) that the logic functions see,
+    /// starting next frame (or the first frame, if the game isn't running yet). Any recording or
+    /// replay already in progress is discarded. Call [`stop_recording`](Engine::stop_recording)
+    /// to get the [`InputRecording`].
+    pub fn start_recording(&mut self) {
+        self.input_recorder = InputRecorder::Recording {
+            recording: InputRecording::default(),
+            filepath: None,
+        };
+    }
+
+    /// Same as [`start_recording`](Engine::start_recording), but the recording is also written to
+    /// a RON file at `filepath` when recording stops, or when the game exits -- whichever happens
+    /// first. If the file can't be written, a message is printed. Use
+    /// [`try_stop_recording`](Engine::try_stop_recording) if you would rather handle that yourself.
+    pub fn record_input_to_file<P: Into<PathBuf>>(&mut self, filepath: P) {
+        self.input_recorder = InputRecorder::Recording {
+            recording: InputRecording::default(),
+            filepath: Some(filepath.into()),
+        };
+    }
+
+    /// Stop recording input and return the recording. Returns `None` if input wasn't being
+    /// recorded. If the recording was started with
+    /// [`record_input_to_file`](Engine::record_input_to_file) and the file can't be written, a
+    /// message is printed. See [`try_stop_recording`](Engine::try_stop_recording).
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        let (recording, filepath) = self.take_recording()?;
+        if let Some(filepath) = filepath
+            && let Err(e) = recording.write_to_file(filepath)
+        {
+            eprintln!("{}", e);
+        }
+        Some(recording)
+    }
+
+    /// Same as [`stop_recording`](Engine::stop_recording), but if the recording was started with
+    /// [`record_input_to_file`](Engine::record_input_to_file) and the file can't be written, the
+    /// [`EngineError`] is returned instead of printing a message.
+    pub fn try_stop_recording(&mut self) -> Result<Option<InputRecording>, EngineError> {
+        let Some((recording, filepath)) = self.take_recording() else {
+            return Ok(None);
+        };
+        if let Some(filepath) = filepath {
+            recording.write_to_file(filepath)?;
+        }
+        Ok(Some(recording))
+    }
+
+    /// Stop recording, and return the recording along with the file it should be written to
+    fn take_recording(&mut self) -> Option<(InputRecording, Option<PathBuf>)> {
+        match std::mem::take(&mut self.input_recorder) {
+            InputRecorder::Recording {
+                recording,
+                filepath,
+            } => Some((recording, filepath)),
+            input_recorder => {
+                self.input_recorder = input_recorder;
+                None
+            }
+        }
+    }
+
+    /// Replay an [`InputRecording`], starting next frame (or the first frame, if the game isn't
+    /// running yet). While replaying, all live keyboard and mouse input is ignored, and `delta` is
+    /// the recorded `delta`. When the recording runs out, live input takes over again. Any
+    /// recording or replay already in progress is discarded.
+    pub fn replay_input(&mut self, recording: InputRecording) {
+        self.input_recorder = InputRecorder::Replaying {
+            recording,
+            next_frame: 0,
+        };
+    }
+
+    /// Stop replaying input, and go back to live input starting next frame.
+    pub fn stop_replay(&mut self) {
+        if self.is_replaying() {
+            self.input_recorder = InputRecorder::Idle;
+        }
+    }
+
+    /// Whether input is currently being recorded
+    pub fn is_recording(&self) -> bool {
+        matches!(self.input_recorder, InputRecorder::Recording { .. })
+    }
+
+    /// Whether input is currently being replayed
+    pub fn is_replaying(&self) -> bool {
+        matches!(self.input_recorder, InputRecorder::Replaying { .. })
+    }
+
+    fn next_logic_handle(&mut self) -> LogicHandle {
+        self.last_logic_handle += 1;
+        LogicHandle(self.last_logic_handle)
+    }
 }
 
 /// startup system - grab window settings, initialize all the starting sprites
 #[doc(hidden)]
 pub fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut engine: ResMut<Engine>) {
-    add_sprites(&mut commands, &asset_server, &mut engine);
-    add_texts(&mut commands, &asset_server, &mut engine);
+    let world_sprites = world_sprites(&engine.sprites);
+    let text_transforms = world_text_transforms(&engine.texts, &engine.sprites);
+    add_sprites(&mut commands, &asset_server, &mut engine, &world_sprites);
+    add_texts(&mut commands, &asset_server, &mut engine, &text_transforms);
 }
 
-/// Add visible lines representing a collider
-fn add_collider_lines(commands: &mut Commands, sprite: &mut Sprite) {
+/// Add visible lines representing a collider. `sprite` should be in world space.
+fn add_collider_lines(commands: &mut Commands, sprite: &Sprite) {
     // Add the collider lines, a visual representation of the sprite's collider
-    let points = sprite.collider.points(); // will be empty vector if NoCollider
+    // The points are scaled and flipped up front (will be empty vector if NoCollider), so that
+    // the lines are the same width no matter how the sprite is scaled
+    let points = sprite.collider.scaled_points(sprite);
     if points.len() >= 2 {
         let mut shape_path = ShapePath::new().move_to(points[0]);
         for point in &points[1..] {
             shape_path = shape_path.line_to(*point);
         }
         shape_path = shape_path.close();
-        let transform = sprite.bevy_transform();
-        let line_width = 1.0 / transform.scale.x;
         commands
             .spawn((
                 ShapeBuilder::with(&shape_path)
-                    .stroke(Stroke::new(Color::WHITE, line_width))
+                    .stroke(Stroke::new(Color::WHITE, 1.0))
                     .build(),
-                transform,
+                collider_lines_transform(sprite),
             ))
             .insert(ColliderLines {
                 sprite_label: sprite.label.clone(),
+                scale: sprite.flipped_scale(),
+                anchor_offset: sprite.anchor_offset(),
             });
     }
-    sprite.collider_dirty = false;
 }
 
-/// helper function: Add Bevy components for all the sprites in engine.sprites
+/// The transform for a sprite's collider lines. The lines are already scaled, so only translation
+/// and rotation are needed. We want collider lines to appear on top of the sprite they are for, so
+/// they need a slightly higher z value. We tell users to only use up to 999.0.
+fn collider_lines_transform(sprite: &Sprite) -> Transform {
+    let mut transform = sprite.bevy_transform();
+    transform.scale = Vec3::ONE;
+    transform.translation.z = (transform.translation.z + 0.1).clamp(0.0, 999.1);
+    transform
+}
+
+/// helper function: Add Bevy components for all the sprites in engine.sprites. `world_sprites`
+/// holds the world space versions of sprites that have a parent.
 #[doc(hidden)]
-pub fn add_sprites(commands: &mut Commands, asset_server: &Res<AssetServer>, engine: &mut Engine) {
+pub fn add_sprites(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    engine: &mut Engine,
+    world_sprites: &HashMap<String, Sprite>,
+) {
     for (_, sprite) in engine.sprites.drain() {
+        engine.tag_index.insert_sprite(&sprite);
         // Create the sprite
-        let transform = sprite.bevy_transform();
+        let transform = world_sprites
+            .get(&sprite.label)
+            .unwrap_or(&sprite)
+            .bevy_transform();
         let texture_path = sprite.filepath.clone();
+        let color = sprite.bevy_color();
+        let anchor = sprite.bevy_anchor();
+        let visibility = sprite.bevy_visibility();
+        // Sprites imported from shapes in a Tiled map have no image
+        let image = if texture_path.as_os_str().is_empty() {
+            Handle::default()
+        } else {
+            asset_server.load(texture_path)
+        };
         commands.spawn((
             sprite,
             BevySprite {
-                image: asset_server.load(texture_path),
+                image,
+                color,
                 ..Default::default()
             },
+            anchor,
             transform,
+            visibility,
         ));
     }
 }
 
 /// Bevy system which adds any needed Bevy components to correspond to the texts in
-/// `engine.texts`
+/// `engine.texts`. `text_transforms` holds the world space transforms of texts that have a parent.
 #[doc(hidden)]
-pub fn add_texts(commands: &mut Commands, asset_server: &Res<AssetServer>, engine: &mut Engine) {
+pub fn add_texts(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    engine: &mut Engine,
+    text_transforms: &HashMap<String, Transform>,
+) {
     for (_, text) in engine.texts.drain() {
-        let transform = text.bevy_transform();
+        let transform = text_transforms
+            .get(&text.label)
+            .copied()
+            .unwrap_or_else(|| text.bevy_transform());
         let font_size = text.font_size;
         let text_string = text.value.clone();
         let font_path = text.font.clone();
+        let visibility = text.bevy_visibility();
         commands.spawn((
             text,
             Text2d(text_string),
@@ -217,6 +896,7 @@ pub fn add_texts(commands: &mut Commands, asset_server: &Res<AssetServer>, engin
             TextColor(Color::WHITE),
             TextLayout::new_with_justify(Justify::Center).with_no_wrap(),
             transform,
+            visibility,
         ));
     }
 }
@@ -243,6 +923,10 @@ pub fn update_window_dimensions(
 #[doc(hidden)]
 pub struct ColliderLines {
     sprite_label: String,
+    // the scale and anchor offset the lines were drawn at, so we can tell when they need to be
+    // redrawn
+    scale: Vec2,
+    anchor_offset: Vec2,
 }
 
 /// A [`Game`] represents the entire game and its data.
@@ -256,6 +940,7 @@ pub struct Game<S: Resource + Send + Sync + 'static> {
     app: App,
     engine: Engine,
     logic_functions: LogicFuncVec<S>,
+    scenes: Scenes<S>,
     window: Window,
 }
 
@@ -263,8 +948,12 @@ impl<S: Resource + Send + Sync + 'static> Default for Game<S> {
     fn default() -> Self {
         Self {
             app: App::new(),
-            engine: Engine::default(),
+            engine: Engine {
+                game_state_type: Some((TypeId::of::<S>(), std::any::type_name::<S>())),
+                ..Default::default()
+            },
             logic_functions: LogicFuncVec(vec![]),
+            scenes: Scenes::default(),
             window: Window {
                 title: "Rusty Engine".into(),
                 ..Default::default()
@@ -274,15 +963,26 @@ impl<S: Resource + Send + Sync + 'static> Default for Game<S> {
 }
 
 impl<S: Resource + Send + Sync + 'static> Game<S> {
-    /// Create an new, empty [`Game`] with an empty [`Engine`]
+    /// Create an new, empty [`Game`] with an empty [`Engine`]. If the `assets/` directory can't be
+    /// found, a message is printed and the program exits. Use [`Game::try_new`] if you would rather
+    /// handle that yourself.
     pub fn new() -> Self {
+        match Self::try_new() {
+            Ok(game) => game,
+            Err(e) => {
+                println!("FATAL: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Create an new, empty [`Game`] with an empty [`Engine`], or return
+    /// [`EngineError::MissingAssetsDirectory`] if the `assets/` directory can't be found.
+    pub fn try_new() -> Result<Self, EngineError> {
         if std::fs::read_dir("assets").is_err() {
-            println!(
-                "FATAL: Could not find assets directory. Have you downloaded the assets?\nhttps://github.com/CleanCut/rusty_engine#you-must-download-the-assets-separately"
-            );
-            std::process::exit(1);
+            return Err(EngineError::MissingAssetsDirectory);
         }
-        Default::default()
+        Ok(Default::default())
     }
 
     /// Use this to set properties of the native OS window before running the game. See the
@@ -295,7 +995,6 @@ impl<S: Resource + Send + Sync + 'static> Game<S> {
 
     /// Start the game.
     pub fn run(&mut self, initial_game_state: S) {
-        self.app.insert_resource::<S>(initial_game_state);
         self.app
             // TODO: Remove this to use the new, darker default color once the videos have been remastered
             .insert_resource(ClearColor(Color::srgb(0.4, 0.4, 0.4)))
@@ -308,40 +1007,158 @@ impl<S: Resource + Send + Sync + 'static> Game<S> {
                     })
                     .set(ImagePlugin::default_nearest()),
             )
-            .add_systems(
-                Update,
-                (
-                    (close_on_esc),
-                    (update_window_dimensions, game_logic_sync::<S>),
-                ),
-            )
+            .add_systems(Update, close_on_esc)
             // External Plugins
             .add_plugins(ShapePlugin) // bevy_prototype_lyon, for displaying sprite colliders
             // Rusty Engine Plugins
+            .add_plugins(AudioManagerPlugin);
+        self.app.world_mut().spawn(Camera2d);
+        self.build(initial_game_state);
+        self.app.run();
+    }
+
+    /// Start the game without a window, audio, or GPU, and return a [`HeadlessGame`] which runs one
+    /// frame at a time when you tell it to. Each frame advances time by exactly `delta`. This is
+    /// mostly useful for automated tests of your game logic. The window dimensions are taken from
+    /// the [`Window`] passed to [`window_settings`](Game::window_settings), so mouse locations work
+    /// the same as they would in a real window of that size.
+    pub fn headless(&mut self, initial_game_state: S, delta: Duration) -> HeadlessGame<S> {
+        self.engine.window_dimensions = Vec2::new(self.window.width(), self.window.height());
+        self.app
+            // Built-ins, minus anything that needs a window, an audio device, or a GPU
             .add_plugins((
-                AudioManagerPlugin,
+                MinimalPlugins,
+                AssetPlugin::default(),
+                ImagePlugin::default_nearest(),
+                TextPlugin,
+                InputPlugin,
+                WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..Default::default()
+                },
+            ))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(delta))
+            .add_systems(Update, discard_managed_audio_system);
+        self.build(initial_game_state);
+        HeadlessGame::new(std::mem::take(&mut self.app), delta)
+    }
+
+    /// Run the game without a window for `frames` frames (or until the game exits), advancing time
+    /// by exactly `delta` each frame, and then return the final [`Engine`] and game state. See
+    /// [`headless`](Game::headless) if you need to inspect the game in between frames.
+    pub fn run_headless(
+        &mut self,
+        initial_game_state: S,
+        frames: u32,
+        delta: Duration,
+    ) -> (Engine, S) {
+        let mut headless_game = self.headless(initial_game_state, delta);
+        headless_game.step_frames(frames);
+        headless_game.finish()
+    }
+
+    /// Add the systems, plugins and resources that Rusty Engine needs whether or not there is a
+    /// window.
+    fn build(&mut self, initial_game_state: S) {
+        self.app.insert_resource::<S>(initial_game_state);
+        self.app
+            .add_systems(Update, (update_window_dimensions, game_logic_sync::<S>))
+            .add_plugins((
+                AnimationPlugin,
                 KeyboardPlugin,
                 MousePlugin,
+                ParticlePlugin,
                 PhysicsPlugin,
+                PixelImagePlugin,
+                RecordingPlugin,
+                ShapesPlugin,
+                SimulatedInputPlugin,
+                TilemapPlugin,
             ))
             //.insert_resource(ReportExecutionOrderAmbiguities) // for debugging
             .add_systems(Startup, setup);
-        self.app.world_mut().spawn(Camera2d);
         let engine = std::mem::take(&mut self.engine);
         self.app.insert_resource(engine);
         let mut logic_functions = LogicFuncVec(vec![]);
         std::mem::swap(&mut self.logic_functions, &mut logic_functions);
         self.app.insert_resource(logic_functions);
-        self.app.run();
+        let scenes = std::mem::take(&mut self.scenes);
+        self.app.insert_resource(scenes);
     }
 
     /// `logic_function` is a function or closure that takes two parameters and returns nothing:
     ///
     /// - `engine: &mut Engine`
-    /// - `game_state`, which is a mutable reference (`&mut`) to the game state struct you defined,
-    ///   or `&mut ()` if you didn't define one.
-    pub fn add_logic(&mut self, logic_function: fn(&mut Engine, &mut S)) {
-        self.logic_functions.0.push(logic_function);
+    /// - `game_state`, which is a mutable reference (`&mut`) to the game state struct you defined.
+    ///
+    /// Closures may capture variables (for example, configuration or an `Arc` shared between logic
+    /// functions), as long as the closure is `Send + Sync`. Logic functions run once each frame in
+    /// the order they were added. Returns a [`LogicHandle`] which you can pass to
+    /// [`Engine::remove_logic`], [`Engine::disable_logic`], or [`Engine::enable_logic`].
+    pub fn add_logic<F>(&mut self, logic_function: F) -> LogicHandle
+    where
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        self.add_logic_with_priority(logic_function, 0)
+    }
+
+    /// Same as [`add_logic`](Game::add_logic), but with a priority. Logic functions run in order
+    /// of priority, lowest first. Logic functions with the same priority run in the order they were
+    /// added. The default priority is `0`.
+    pub fn add_logic_with_priority<F>(&mut self, logic_function: F, priority: i32) -> LogicHandle
+    where
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        let logic_function =
+            LogicFunction::new(self.engine.next_logic_handle(), priority, logic_function);
+        let handle = logic_function.handle;
+        self.logic_functions.insert(logic_function);
+        handle
+    }
+
+    /// Add a fixed logic function, which runs `hz` times per second of game time no matter how
+    /// fast or slow frames are rendered. Use it for physics-like movement that needs to be
+    /// deterministic. Inside the function, use [`Engine::fixed_delta_f32`] instead of
+    /// `delta_f32`. If a frame takes longer than one fixed step, the function runs several times
+    /// that frame to catch up (up to a limit of ten times per frame). Sprites and collision
+    /// events are kept up to date in between each fixed step. Fixed logic functions run before the
+    /// regular logic functions each frame. While there is a fixed logic function, sprites move by
+    /// their [`velocity`](crate::sprite::Sprite::velocity) in its fixed steps (the first one's, if
+    /// there are several) instead of once per frame, so fixed logic sees the motion. `hz` is limited to between `0.001` and `10000.0`, and a
+    /// `hz` that isn't a positive number is replaced with `60.0`.
+    pub fn add_fixed_logic<F>(&mut self, hz: f32, logic_function: F) -> LogicHandle
+    where
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        let logic_function = LogicFunction::new(self.engine.next_logic_handle(), 0, logic_function)
+            .with_fixed_timestep(hz);
+        let handle = logic_function.handle;
+        self.logic_functions.insert(logic_function);
+        handle
+    }
+
+    /// Add a [`Scene`] named `name` to the game. A scene's logic functions only run while it is the
+    /// current scene. Choose the first scene by calling [`Engine::switch_scene`] before running the
+    /// game, and switch to other scenes by calling it from a logic function. Each scene should have
+    /// a unique name.
+    pub fn add_scene<T: Into<String>>(&mut self, name: T, mut scene: Scene<S>) {
+        let name = name.into();
+        for scene_logic in scene.logic_functions.drain(..) {
+            let mut logic_function = LogicFunction {
+                handle: self.engine.next_logic_handle(),
+                priority: scene_logic.priority,
+                enabled: true,
+                scene: Some(name.clone()),
+                fixed_timestep: None,
+                func: scene_logic.func,
+            };
+            if let Some(hz) = scene_logic.hz {
+                logic_function = logic_function.with_fixed_timestep(hz);
+            }
+            self.logic_functions.insert(logic_function);
+        }
+        self.scenes.0.insert(name, scene);
     }
 }
 
@@ -350,24 +1167,38 @@ impl<S: Resource + Send + Sync + 'static> Game<S> {
 fn game_logic_sync<S: Resource + Send + Sync + 'static>(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
     mut engine: ResMut<Engine>,
     mut game_state: ResMut<S>,
-    logic_functions: Res<LogicFuncVec<S>>,
+    mut logic_functions: ResMut<LogicFuncVec<S>>,
+    mut scenes: ResMut<Scenes<S>>,
     keyboard_state: Res<KeyboardState>,
     mouse_state: Res<MouseState>,
     time: Res<Time>,
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut existing_collisions: ResMut<ExistingCollisions>,
     mut app_exit_events: MessageWriter<AppExit>,
     mut collision_events: MessageReader<CollisionEvent>,
     mut query_set: ParamSet<(
-        Query<(Entity, &mut Sprite, &mut Transform)>,
+        Query<(
+            Entity,
+            &mut Sprite,
+            &mut Transform,
+            &mut BevySprite,
+            &mut BevyAnchor,
+            &mut Visibility,
+        )>,
         Query<(
             Entity,
             &mut Text,
             &mut Transform,
             &mut Text2d,
             &mut TextFont,
+            &mut Visibility,
         )>,
-        Query<(Entity, &mut Shape, &mut Transform, &ColliderLines)>,
+        Query<(Entity, &mut Transform, &ColliderLines)>,
+        Query<Entity, With<PrimaryWindow>>,
     )>,
 ) {
     // Update this frame's timing info
@@ -375,6 +1206,8 @@ fn game_logic_sync<S: Resource + Send + Sync + 'static>(
     engine.delta_f32 = time.delta_secs();
     engine.time_since_startup = time.elapsed();
     engine.time_since_startup_f64 = time.elapsed_secs_f64();
+    engine.real_delta = real_time.delta();
+    engine.real_delta_f32 = real_time.delta_secs();
 
     // Copy keyboard state over to engine to give to users
     engine.keyboard_state = keyboard_state.clone();
@@ -388,41 +1221,92 @@ fn game_logic_sync<S: Resource + Send + Sync + 'static>(
         engine.collision_events.push(collision_event.clone());
     }
 
-    // Copy all sprites over to the engine to give to users
+    // Copy all sprites over to the engine to give to users, along with the size of their images
     engine.sprites.clear();
-    for (_, sprite, _) in query_set.p0().iter() {
-        let _ = engine
-            .sprites
-            .insert(sprite.label.clone(), (*sprite).clone());
+    engine.all_sprites_loaded = true;
+    for (_, sprite, _, bevy_sprite, _, _) in query_set.p0().iter() {
+        let mut sprite = (*sprite).clone();
+        sprite.image_size = images
+            .get(&bevy_sprite.image)
+            .map(|image| sheet_frame_size(&engine, &sprite).unwrap_or_else(|| image.size_f32()));
+        if !sprite.filepath.as_os_str().is_empty()
+            && !matches!(
+                asset_server.load_state(bevy_sprite.image.id()),
+                LoadState::Loaded | LoadState::Failed(_)
+            )
+        {
+            engine.all_sprites_loaded = false;
+        }
+        let _ = engine.sprites.insert(sprite.label.clone(), sprite);
     }
-
     // Copy all texts over to the engine to give to users
     engine.texts.clear();
-    for (_, text, _, _, _) in query_set.p1().iter() {
+    for (_, text, _, _, _, _) in query_set.p1().iter() {
         let _ = engine.texts.insert(text.label.clone(), (*text).clone());
     }
 
+    // Record this frame's input, or replace it with recorded input. There won't be a window if
+    // we're running headless.
+    let window = query_set.p3().single().unwrap_or(Entity::PLACEHOLDER);
+    InputRecorder::update(&mut engine, window);
+
+    // Move all the animations, tweens, and paths along
+    advance_animations(&mut engine);
+    advance_tweens(&mut engine);
+    advance_paths(&mut engine);
+
     // Perform all the user's game logic for this frame
-    for func in logic_functions.0.iter() {
-        func(&mut engine, &mut game_state);
+    logic_functions.apply_commands(&mut engine);
+    let moved = logic_functions.run_fixed(&mut engine, &mut game_state, &mut existing_collisions);
+    for logic_function in logic_functions.0.iter_mut() {
+        if logic_function.is_active(&engine) && logic_function.fixed_timestep.is_none() {
+            (logic_function.func)(&mut engine, &mut game_state);
+        }
+    }
+    logic_functions.apply_commands(&mut engine);
+    scenes.switch_scene(&mut engine, &mut game_state);
+
+    // Move sprites along by their velocity, so collisions are detected where they end up. If
+    // there are fixed logic functions, that already happened in the fixed steps.
+    if !moved {
+        let delta = engine.delta_f32;
+        for sprite in engine.sprites.values_mut() {
+            sprite.apply_motion(delta);
+        }
     }
 
+    // Work out where attached sprites and texts are in world space, while all their parents are
+    // still around to be looked up
+    let world_sprites = world_sprites(&engine.sprites);
+    let text_transforms = world_text_transforms(&engine.texts, &engine.sprites);
+
     if !engine.last_show_colliders && engine.show_colliders {
         // Just turned on show_colliders -- create collider lines for all sprites
         for sprite in engine.sprites.values_mut() {
-            add_collider_lines(&mut commands, sprite);
+            add_collider_lines(
+                &mut commands,
+                world_sprites.get(&sprite.label).unwrap_or(sprite),
+            );
+            sprite.collider_dirty = false;
         }
     } else if engine.last_show_colliders && !engine.show_colliders {
         // Just turned off show_colliders -- delete collider lines for all sprites
-        for (entity, _, _, _) in query_set.p2().iter_mut() {
+        for (entity, _, _) in query_set.p2().iter_mut() {
             commands.entity(entity).despawn();
         }
     }
-    // Update transform & line width of all collider lines
+    // Update all the collider lines
     if engine.show_colliders {
-        // Delete collider lines for sprites which are missing, or whose colliders are dirty
-        for (entity, _, _, collider_lines) in query_set.p2().iter_mut() {
-            if let Some(sprite) = engine.sprites.get(&collider_lines.sprite_label) {
+        // Delete collider lines for sprites which are missing, or whose colliders are dirty or
+        // have been scaled, flipped or re-anchored
+        for (entity, _, collider_lines) in query_set.p2().iter_mut() {
+            if let Some(sprite) = engine.sprites.get_mut(&collider_lines.sprite_label) {
+                let world_sprite = world_sprites.get(&sprite.label).unwrap_or(sprite);
+                if world_sprite.flipped_scale() != collider_lines.scale
+                    || world_sprite.anchor_offset() != collider_lines.anchor_offset
+                {
+                    sprite.collider_dirty = true;
+                }
                 if sprite.collider_dirty {
                     commands.entity(entity).despawn();
                 }
@@ -433,46 +1317,70 @@ fn game_logic_sync<S: Resource + Send + Sync + 'static>(
         // Add collider lines for sprites whose colliders are dirty
         for sprite in engine.sprites.values_mut() {
             if sprite.collider_dirty {
-                add_collider_lines(&mut commands, sprite);
+                add_collider_lines(
+                    &mut commands,
+                    world_sprites.get(&sprite.label).unwrap_or(sprite),
+                );
+                sprite.collider_dirty = false;
             }
         }
-        // Update transform & line width
-        for (_, mut shape, mut transform, collider_lines) in query_set.p2().iter_mut() {
+        // Update transform
+        for (_, mut transform, collider_lines) in query_set.p2().iter_mut() {
             if let Some(sprite) = engine.sprites.get(&collider_lines.sprite_label) {
-                *transform = sprite.bevy_transform();
-                // We want collider lines to appear on top of the sprite they are for, so they need a
-                // slightly higher z value. We tell users to only use up to 999.0.
-                transform.translation.z = (transform.translation.z + 0.1).clamp(0.0, 999.1);
-            }
-            // Stroke line width gets scaled with the transform, but we want it to appear to be the same
-            // regardless of scale, so we have to counter the scale.
-            if let Some(ref mut stroke) = shape.stroke {
-                stroke.options.line_width = 1.0 / transform.scale.x;
+                *transform =
+                    collider_lines_transform(world_sprites.get(&sprite.label).unwrap_or(sprite));
             }
         }
     }
     engine.last_show_colliders = engine.show_colliders;
 
     // Transfer any changes in the user's Sprite copies to the Bevy Sprite and Transform components
-    for (entity, mut sprite, mut transform) in query_set.p0().iter_mut() {
+    for (entity, mut sprite, mut transform, mut bevy_sprite, mut bevy_anchor, mut visibility) in
+        query_set.p0().iter_mut()
+    {
         if let Some(sprite_copy) = engine.sprites.remove(&sprite.label) {
+            if sprite_copy.tags != sprite.tags {
+                engine
+                    .tag_index
+                    .retag(&sprite.label, &sprite.tags, &sprite_copy.tags);
+            }
             *sprite = sprite_copy;
-            *transform = sprite.bevy_transform();
+            *transform = world_sprites
+                .get(&sprite.label)
+                .unwrap_or(&sprite)
+                .bevy_transform();
+            let color = sprite.bevy_color();
+            if bevy_sprite.color != color {
+                bevy_sprite.color = color;
+            }
+            let anchor = sprite.bevy_anchor();
+            if *bevy_anchor != anchor {
+                *bevy_anchor = anchor;
+            }
+            let sprite_visibility = sprite.bevy_visibility();
+            if *visibility != sprite_visibility {
+                *visibility = sprite_visibility;
+            }
         } else {
+            engine.tag_index.remove_sprite(&sprite);
             commands.entity(entity).despawn();
         }
     }
 
     // Add Bevy components for any new sprites remaining in engine.sprites
-    add_sprites(&mut commands, &asset_server, &mut engine);
+    add_sprites(&mut commands, &asset_server, &mut engine, &world_sprites);
+    engine.tag_index.clear_added();
 
     // Transfer any changes in the user's Texts to the Bevy Text and Transform components
-    for (entity, mut text, mut transform, mut bevy_text_component, mut text_font) in
+    for (entity, mut text, mut transform, mut bevy_text_component, mut text_font, mut visibility) in
         query_set.p1().iter_mut()
     {
         if let Some(text_copy) = engine.texts.remove(&text.label) {
             *text = text_copy;
-            *transform = text.bevy_transform();
+            *transform = text_transforms
+                .get(&text.label)
+                .copied()
+                .unwrap_or_else(|| text.bevy_transform());
             if text.value != bevy_text_component.0 {
                 bevy_text_component.0 = text.value.clone();
             }
@@ -484,13 +1392,27 @@ fn game_logic_sync<S: Resource + Send + Sync + 'static>(
             if text_font.font != font {
                 text_font.font = font;
             }
+            let text_visibility = text.bevy_visibility();
+            if *visibility != text_visibility {
+                *visibility = text_visibility;
+            }
         } else {
             commands.entity(entity).despawn();
         }
     }
 
     // Add Bevy components for any new texts remaining in engine.texts
-    add_texts(&mut commands, &asset_server, &mut engine);
+    add_texts(&mut commands, &asset_server, &mut engine, &text_transforms);
+
+    // Apply any changes to the flow of time, which take effect next frame
+    if engine.time_scale.is_finite() {
+        virtual_time.set_relative_speed(engine.time_scale.max(0.0));
+    }
+    if engine.paused {
+        virtual_time.pause();
+    } else {
+        virtual_time.unpause();
+    }
 
     if engine.should_exit {
         app_exit_events.write(AppExit::Success);
@@ -513,8 +1435,200 @@ impl<S: Resource + Send + Sync + 'static> DerefMut for Game<S> {
     }
 }
 
+/// Identifies a logic function that was added with [`Game::add_logic`] or [`Engine::add_logic`],
+/// so that it can be removed, disabled, or enabled later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LogicHandle(u64);
+
+/// A logic function along with the bookkeeping needed to manage it at runtime
+struct LogicFunction<S: Resource + Send + Sync + 'static> {
+    handle: LogicHandle,
+    priority: i32,
+    enabled: bool,
+    // only run while this scene is the current scene
+    scene: Option<String>,
+    fixed_timestep: Option<FixedTimestep>,
+    func: BoxedLogic<S>,
+}
+
+/// A boxed logic function or closure
+pub(crate) type BoxedLogic<S> = Box<dyn FnMut(&mut Engine, &mut S) + Send + Sync>;
+
+/// The most fixed steps a single fixed logic function may run in one frame. Any more than that, and
+/// a slow frame could cause an even slower frame, and so on.
+const MAX_FIXED_STEPS_PER_FRAME: u32 = 10;
+
+/// The slowest and fastest rates a fixed logic function may run at, in steps per second. The
+/// fastest rate keeps each step long enough to drain the time that has built up.
+const MIN_FIXED_HZ: f32 = 0.001;
+const MAX_FIXED_HZ: f32 = 10_000.0;
+
+/// The rate used in place of a `hz` that isn't a positive number
+const DEFAULT_FIXED_HZ: f32 = 60.0;
+
+/// How often a fixed logic function runs, and how much time has built up toward its next step
+struct FixedTimestep {
+    step: Duration,
+    accumulator: Duration,
+}
+
+impl<S: Resource + Send + Sync + 'static> LogicFunction<S> {
+    fn new<F>(handle: LogicHandle, priority: i32, func: F) -> Self
+    where
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        Self {
+            handle,
+            priority,
+            enabled: true,
+            scene: None,
+            fixed_timestep: None,
+            func: Box::new(func),
+        }
+    }
+
+    /// Whether the logic function should run this frame
+    fn is_active(&self, engine: &Engine) -> bool {
+        self.enabled && (self.scene.is_none() || self.scene == engine.current_scene)
+    }
+
+    fn with_fixed_timestep(mut self, hz: f32) -> Self {
+        let hz = if hz.is_finite() && hz > 0.0 {
+            hz.clamp(MIN_FIXED_HZ, MAX_FIXED_HZ)
+        } else {
+            warn!(
+                "Fixed logic rate {hz} is not a positive number, using {DEFAULT_FIXED_HZ} instead"
+            );
+            DEFAULT_FIXED_HZ
+        };
+        self.fixed_timestep = Some(FixedTimestep {
+            step: Duration::from_secs_f64(1.0 / hz as f64),
+            accumulator: Duration::ZERO,
+        });
+        self
+    }
+}
+
+/// A change to the logic functions requested through the [`Engine`]. Added logic functions are
+/// type-erased, since the [`Engine`] doesn't know the type of the game state.
+#[derive(Debug)]
+enum LogicCommand {
+    Add(Box<dyn Any + Send + Sync>),
+    Remove(LogicHandle),
+    Disable(LogicHandle),
+    Enable(LogicHandle),
+}
+
+/// All the logic functions, kept sorted by priority
 #[derive(Resource)]
-struct LogicFuncVec<S: Resource + Send + Sync + 'static>(Vec<fn(&mut Engine, &mut S)>);
+struct LogicFuncVec<S: Resource + Send + Sync + 'static>(Vec<LogicFunction<S>>);
+
+impl<S: Resource + Send + Sync + 'static> LogicFuncVec<S> {
+    fn insert(&mut self, logic_function: LogicFunction<S>) {
+        // Go after everything with the same priority, so ties run in the order they were added
+        let index = self
+            .0
+            .partition_point(|l| l.priority <= logic_function.priority);
+        self.0.insert(index, logic_function);
+    }
+
+    fn apply_commands(&mut self, engine: &mut Engine) {
+        for command in std::mem::take(&mut engine.logic_commands) {
+            match command {
+                LogicCommand::Add(logic_function) => {
+                    match logic_function.downcast::<LogicFunction<S>>() {
+                        Ok(logic_function) => self.insert(*logic_function),
+                        Err(_) => warn!(
+                            "Ignoring a logic function whose game state type is not {}",
+                            std::any::type_name::<S>()
+                        ),
+                    }
+                }
+                LogicCommand::Remove(handle) => self.0.retain(|l| l.handle != handle),
+                LogicCommand::Disable(handle) => self.set_enabled(handle, false),
+                LogicCommand::Enable(handle) => self.set_enabled(handle, true),
+            }
+        }
+    }
+
+    /// Run each enabled fixed logic function once for each of its fixed steps that fit in the time
+    /// that has built up, bringing collisions up to date after each round of steps. Sprites are
+    /// moved by their velocity in each step of the first fixed logic function, before collisions
+    /// are checked. Returns whether there was a fixed logic function to move the sprites.
+    fn run_fixed(
+        &mut self,
+        engine: &mut Engine,
+        game_state: &mut S,
+        existing_collisions: &mut ExistingCollisions,
+    ) -> bool {
+        let motion_handle = self
+            .0
+            .iter()
+            .find(|l| l.is_active(engine) && l.fixed_timestep.is_some())
+            .map(|l| l.handle);
+        for logic_function in self.0.iter_mut().filter(|l| l.is_active(engine)) {
+            if let Some(timestep) = &mut logic_function.fixed_timestep {
+                timestep.accumulator = (timestep.accumulator + engine.delta)
+                    .min(timestep.step * MAX_FIXED_STEPS_PER_FRAME);
+            }
+        }
+        loop {
+            let mut any_ran = false;
+            let mut motion_step = None;
+            for logic_function in self.0.iter_mut() {
+                if !logic_function.is_active(engine) {
+                    continue;
+                }
+                let Some(timestep) = &mut logic_function.fixed_timestep else {
+                    continue;
+                };
+                if timestep.accumulator < timestep.step {
+                    continue;
+                }
+                timestep.accumulator -= timestep.step;
+                engine.fixed_delta = timestep.step;
+                engine.fixed_delta_f32 = timestep.step.as_secs_f32();
+                (logic_function.func)(engine, game_state);
+                any_ran = true;
+                if Some(logic_function.handle) == motion_handle {
+                    motion_step = Some(timestep.step.as_secs_f32());
+                }
+            }
+            if !any_ran {
+                break;
+            }
+            if let Some(step) = motion_step {
+                for sprite in engine.sprites.values_mut() {
+                    sprite.apply_motion(step);
+                }
+            }
+            let collision_events = detect_collisions(
+                engine.sprites.values(),
+                engine.tilemaps.values(),
+                engine.shapes.values(),
+                existing_collisions,
+            );
+            engine.collision_events.extend(collision_events);
+        }
+        engine.fixed_alphas.clear();
+        for logic_function in self.0.iter() {
+            if let Some(timestep) = &logic_function.fixed_timestep {
+                let alpha = timestep.accumulator.as_secs_f32() / timestep.step.as_secs_f32();
+                if engine.fixed_alphas.is_empty() {
+                    engine.fixed_alpha = alpha;
+                }
+                engine.fixed_alphas.insert(logic_function.handle, alpha);
+            }
+        }
+        motion_handle.is_some()
+    }
+
+    fn set_enabled(&mut self, handle: LogicHandle, enabled: bool) {
+        if let Some(logic_function) = self.0.iter_mut().find(|l| l.handle == handle) {
+            logic_function.enabled = enabled;
+        }
+    }
+}
 
 pub fn close_on_esc(
     mut commands: Commands,
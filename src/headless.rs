@@ -0,0 +1,162 @@
+//! Facilities for running a game without a window, which is mostly useful for automated tests of
+//! your game logic. Create a [`HeadlessGame`] with [`Game::headless`], or run a fixed number of
+//! frames in one go with [`Game::run_headless`].
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! # use std::time::Duration;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState { score: u32 }
+//! #
+//! # fn logic(engine: &mut Engine, game_state: &mut GameState) {}
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! let _ = game.add_sprite("player", SpritePreset::RacingCarBlue);
+//! game.add_logic(logic);
+//! let mut headless = game.headless(GameState { score: 0 }, Duration::from_secs_f32(1.0 / 60.0));
+//! headless.step_frames(60);
+//! assert!(headless.engine().sprites.contains_key("player"));
+//! let (_engine, _game_state) = headless.finish();
+//! # }
+//! ```
+//!
+//! [`Game::headless`]: crate::prelude::Game::headless
+//! [`Game::run_headless`]: crate::prelude::Game::run_headless
+
+use crate::{input::SimulatedInput, prelude::Engine, sprite::Sprite, text::Text};
+use bevy::{
+    prelude::*,
+    time::{Real, Virtual},
+};
+use std::{marker::PhantomData, time::Duration};
+
+/// A [`HeadlessGame`] is a game that runs without a window, audio, or GPU. Frames only run when
+/// you call [`step`](HeadlessGame::step) or [`step_frames`](HeadlessGame::step_frames), and each
+/// frame advances time by exactly the same `delta`, so the results are repeatable. Your logic
+/// functions, collision detection, and the keyboard and mouse state all work the same way they do
+/// in a normal game. Images are never actually loaded, since there is nothing to draw them on.
+pub struct HeadlessGame<S: Resource + Send + Sync + 'static> {
+    app: App,
+    delta: Duration,
+    frame: u64,
+    exited: bool,
+    _game_state: PhantomData<S>,
+}
+
+impl<S: Resource + Send + Sync + 'static> HeadlessGame<S> {
+    pub(crate) fn new(mut app: App, delta: Duration) -> Self {
+        app.finish();
+        app.cleanup();
+        // Start the clock now, so that the very first frame advances by `delta` instead of zero
+        app.world_mut()
+            .resource_mut::<Time<Real>>()
+            .update_with_duration(Duration::ZERO);
+        // Bevy normally clamps very large deltas, but a large delta is what was asked for
+        let mut virtual_time = app.world_mut().resource_mut::<Time<Virtual>>();
+        let max_delta = virtual_time.max_delta().max(delta);
+        virtual_time.set_max_delta(max_delta);
+        Self {
+            app,
+            delta,
+            frame: 0,
+            exited: false,
+            _game_state: PhantomData,
+        }
+    }
+
+    /// Run a single frame. Returns `false` without running anything if the game has already
+    /// exited (see [`Engine::should_exit`]).
+    pub fn step(&mut self) -> bool {
+        if self.exited {
+            return false;
+        }
+        self.app.update();
+        self.frame += 1;
+        self.exited = self.app.should_exit().is_some();
+        true
+    }
+
+    /// Run up to `frames` frames, stopping early if the game exits. Returns the number of frames
+    /// that were actually run.
+    pub fn step_frames(&mut self, frames: u32) -> u32 {
+        let mut frames_run = 0;
+        while frames_run < frames && self.step() {
+            frames_run += 1;
+        }
+        frames_run
+    }
+
+    /// The number of frames that have been run so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The fixed delta that each frame advances time by.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Whether the game has exited by setting [`Engine::should_exit`].
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /// The [`Engine`] as it was at the end of the last frame, including all of its sprites and
+    /// texts. Changes to the engine can only be made from your logic functions.
+    pub fn engine(&mut self) -> &Engine {
+        self.sync_engine();
+        self.app.world().resource::<Engine>()
+    }
+
+    /// Simulate keyboard and mouse input for the next frame. See [`SimulatedInput`].
+    pub fn simulated_input(&mut self) -> &mut SimulatedInput {
+        &mut self
+            .app
+            .world_mut()
+            .resource_mut::<Engine>()
+            .into_inner()
+            .simulated_input
+    }
+
+    /// Your game state as it was at the end of the last frame.
+    pub fn game_state(&self) -> &S {
+        self.app.world().resource::<S>()
+    }
+
+    /// Your game state, for making changes in between frames.
+    pub fn game_state_mut(&mut self) -> Mut<'_, S> {
+        self.app.world_mut().resource_mut::<S>()
+    }
+
+    /// Stop the game and hand back the final [`Engine`] and game state.
+    pub fn finish(mut self) -> (Engine, S) {
+        self.sync_engine();
+        let world = self.app.world_mut();
+        // Unwraps: Both resources are inserted before a HeadlessGame is ever created
+        let engine = world.remove_resource::<Engine>().unwrap();
+        let game_state = world.remove_resource::<S>().unwrap();
+        (engine, game_state)
+    }
+
+    /// Sprites and texts live in Bevy in between frames, so copy them back into the engine
+    fn sync_engine(&mut self) {
+        // Before the first frame, the sprites and texts haven't been handed over to Bevy yet
+        if self.frame == 0 {
+            return;
+        }
+        let world = self.app.world_mut();
+        let sprites: Vec<Sprite> = world.query::<&Sprite>().iter(world).cloned().collect();
+        let texts: Vec<Text> = world.query::<&Text>().iter(world).cloned().collect();
+        let mut engine = world.resource_mut::<Engine>();
+        engine.sprites = sprites
+            .into_iter()
+            .map(|sprite| (sprite.label.clone(), sprite))
+            .collect();
+        engine.texts = texts
+            .into_iter()
+            .map(|text| (text.label.clone(), text))
+            .collect();
+    }
+}
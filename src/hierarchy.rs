@@ -0,0 +1,154 @@
+//! Facilities for attaching sprites and texts to a parent sprite, so that compound objects (a tank
+//! with a rotating turret, a car with a name tag) move together.
+//!
+//! Set the `parent` field of a [`Sprite`] or [`Text`](crate::prelude::Text) to the label of another
+//! sprite, and its `translation`, `rotation` and `scale` become relative to that sprite. Use
+//! [`Engine::sprite_world_transform`](crate::prelude::Engine::sprite_world_transform) and
+//! [`Engine::text_world_transform`](crate::prelude::Engine::text_world_transform) to find out
+//! where things ended up in world space.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! let _ = game.add_sprite("car", SpritePreset::RacingCarBlue);
+//! let name_tag = game.add_text("name_tag", "Player 1");
+//! name_tag.parent = Some("car".into());
+//! name_tag.translation.y = 50.0; // Always 50 pixels "above" the car, even when it turns
+//! game.run(GameState);
+//! # }
+//! ```
+
+use crate::{sprite::Sprite, text::Text};
+use bevy::{
+    platform::collections::HashMap,
+    prelude::{Quat, Transform, Vec2, Vec3, warn},
+};
+use std::borrow::Cow;
+
+/// The translation, rotation and scale of a sprite or text in world space, after its parent (and
+/// its parent's parent, and so on) have been taken into account.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldTransform {
+    /// Where the sprite or text is in world space
+    pub translation: Vec2,
+    /// The direction the sprite or text faces in world space, in radians
+    pub rotation: f32,
+    /// The scale along the x and y axes. Negative if the sprite is flipped along that axis.
+    pub scale: Vec2,
+}
+
+impl WorldTransform {
+    /// Place `child` (which is relative to `self`) into the space that `self` is in. Scale and
+    /// flipping carry over from parent to child, but rotation is not mirrored by flipping.
+    pub fn then(self, child: WorldTransform) -> WorldTransform {
+        WorldTransform {
+            translation: self.translation
+                + Vec2::from_angle(self.rotation).rotate(child.translation * self.scale),
+            rotation: self.rotation + child.rotation,
+            scale: self.scale * child.scale,
+        }
+    }
+
+    /// The sprite's own transform, relative to its parent
+    pub(crate) fn local(sprite: &Sprite) -> Self {
+        Self {
+            translation: sprite.translation,
+            rotation: sprite.rotation,
+            scale: sprite.flipped_scale(),
+        }
+    }
+
+    /// The text's own transform, relative to its parent
+    pub(crate) fn local_text(text: &Text) -> Self {
+        Self {
+            translation: text.translation,
+            rotation: text.rotation,
+            scale: Vec2::splat(text.scale),
+        }
+    }
+
+    /// Resolve a transform relative to the sprite labeled `parent` (if any) into world space.
+    /// `lookup` finds sprites by label. A missing parent is treated as the world itself, and a loop
+    /// of parents is cut short.
+    pub(crate) fn resolve<'a>(
+        mut self,
+        mut parent: Option<&'a str>,
+        lookup: impl Fn(&str) -> Option<&'a Sprite>,
+    ) -> Self {
+        // A chain longer than this must be going around in a loop
+        const MAX_DEPTH: usize = 64;
+        let mut depth = 0;
+        while let Some(parent_sprite) = parent.and_then(&lookup) {
+            self = Self::local(parent_sprite).then(self);
+            parent = parent_sprite.parent.as_deref();
+            depth += 1;
+            if depth >= MAX_DEPTH {
+                warn!("Sprite parents form a loop at {:?}", parent_sprite.label);
+                break;
+            }
+        }
+        self
+    }
+
+    /// Do the math to convert to Bevy's Transform
+    pub(crate) fn bevy_transform(&self, layer: f32) -> Transform {
+        let mut transform = Transform::from_translation(self.translation.extend(layer));
+        transform.rotation = Quat::from_axis_angle(Vec3::Z, self.rotation);
+        transform.scale = self.scale.extend(1.0);
+        transform
+    }
+}
+
+/// A sprite in world space: sprites without a parent are borrowed as they are, and sprites with a
+/// parent are copied with their world transform filled in.
+pub(crate) fn world_sprite<'a>(
+    sprite: &'a Sprite,
+    lookup: impl Fn(&str) -> Option<&'a Sprite>,
+) -> Cow<'a, Sprite> {
+    if sprite.parent.is_none() {
+        return Cow::Borrowed(sprite);
+    }
+    let world = WorldTransform::local(sprite).resolve(sprite.parent.as_deref(), lookup);
+    let mut sprite = sprite.clone();
+    sprite.parent = None;
+    sprite.translation = world.translation;
+    sprite.rotation = world.rotation;
+    sprite.scale_xy = Some(world.scale.abs());
+    sprite.flip_x = world.scale.x < 0.0;
+    sprite.flip_y = world.scale.y < 0.0;
+    Cow::Owned(sprite)
+}
+
+/// World space copies of all the sprites that have a parent, by label. Sprites without a parent are
+/// already in world space, so they are left out.
+pub(crate) fn world_sprites(sprites: &HashMap<String, Sprite>) -> HashMap<String, Sprite> {
+    sprites
+        .values()
+        .filter(|sprite| sprite.parent.is_some())
+        .map(|sprite| {
+            let world = world_sprite(sprite, |label| sprites.get(label)).into_owned();
+            (sprite.label.clone(), world)
+        })
+        .collect()
+}
+
+/// World space Bevy transforms of all the texts that have a parent, by label
+pub(crate) fn world_text_transforms(
+    texts: &HashMap<String, Text>,
+    sprites: &HashMap<String, Sprite>,
+) -> HashMap<String, Transform> {
+    texts
+        .values()
+        .filter(|text| text.parent.is_some())
+        .map(|text| {
+            let world = WorldTransform::local_text(text)
+                .resolve(text.parent.as_deref(), |label| sprites.get(label));
+            (text.label.clone(), world.bevy_transform(text.layer))
+        })
+        .collect()
+}
@@ -0,0 +1,189 @@
+//! Facilities for simulating keyboard and mouse input
+//!
+//! Simulated input is fed into Bevy exactly as if it came from a real keyboard or mouse, so it
+//! shows up in [`Engine::keyboard_state`], [`Engine::keyboard_events`],
+//! [`Engine::mouse_state`], [`Engine::mouse_button_events`], and the rest of the input fields on
+//! the following frame. This is handy for bots, demos, and automated tests.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! // Inside your logic function...
+//! # fn logic(engine: &mut Engine, game_state: &mut GameState) {
+//! engine.simulated_input.press_key(KeyCode::ArrowRight);
+//! engine.simulated_input.move_mouse_to(Vec2::new(100.0, -50.0));
+//! engine.simulated_input.click(MouseButton::Left);
+//! # }
+//! ```
+
+use crate::prelude::Engine;
+use bevy::{
+    input::{
+        ButtonState, InputSystems,
+        keyboard::{Key, KeyCode, KeyboardInput, NativeKey},
+        mouse::{MouseButton, MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+    },
+    prelude::*,
+    window::{CursorMoved, PrimaryWindow},
+};
+
+pub(crate) struct SimulatedInputPlugin;
+
+impl Plugin for SimulatedInputPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(PreUpdate, send_simulated_input.before(InputSystems));
+    }
+}
+
+/// A single piece of simulated input, waiting to be sent
+#[derive(Clone, Debug)]
+enum SimulatedEvent {
+    Key(KeyCode, ButtonState),
+    MouseButton(MouseButton, ButtonState),
+    /// Location in game space
+    CursorMoved(Vec2),
+    MouseWheel(Vec2),
+}
+
+/// Use a [`SimulatedInput`] to press and release keys, move the mouse, click mouse buttons, and
+/// scroll the mouse wheel from code. It is exposed through the [`Engine`] struct provided to your
+/// logic function each frame as the
+/// [`simulated_input`](crate::prelude::Engine::simulated_input) field. Input is queued up and sent
+/// at the very start of the next frame.
+#[derive(Clone, Debug, Default)]
+pub struct SimulatedInput {
+    queue: Vec<SimulatedEvent>,
+    next_queue: Vec<SimulatedEvent>,
+    location: Option<Vec2>,
+}
+
+impl SimulatedInput {
+    /// Start pressing a key. The key stays pressed until you call
+    /// [`release_key`](SimulatedInput::release_key).
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.queue
+            .push(SimulatedEvent::Key(key, ButtonState::Pressed));
+    }
+    /// Stop pressing a key.
+    pub fn release_key(&mut self, key: KeyCode) {
+        self.queue
+            .push(SimulatedEvent::Key(key, ButtonState::Released));
+    }
+    /// Press a key next frame, and release it the frame after.
+    pub fn tap_key(&mut self, key: KeyCode) {
+        self.press_key(key);
+        self.next_queue
+            .push(SimulatedEvent::Key(key, ButtonState::Released));
+    }
+    /// Start pressing a mouse button. The button stays pressed until you call
+    /// [`release_mouse_button`](SimulatedInput::release_mouse_button).
+    pub fn press_mouse_button(&mut self, mouse_button: MouseButton) {
+        self.queue.push(SimulatedEvent::MouseButton(
+            mouse_button,
+            ButtonState::Pressed,
+        ));
+    }
+    /// Stop pressing a mouse button.
+    pub fn release_mouse_button(&mut self, mouse_button: MouseButton) {
+        self.queue.push(SimulatedEvent::MouseButton(
+            mouse_button,
+            ButtonState::Released,
+        ));
+    }
+    /// Press a mouse button next frame, and release it the frame after.
+    pub fn click(&mut self, mouse_button: MouseButton) {
+        self.press_mouse_button(mouse_button);
+        self.next_queue.push(SimulatedEvent::MouseButton(
+            mouse_button,
+            ButtonState::Released,
+        ));
+    }
+    /// Move the mouse to a location in game space. Positive x is right. Positive y is up. (0.0,
+    /// 0.0) is the center of the screen. Just like a real mouse, this produces a mouse location
+    /// event, and also a mouse motion event if the mouse was already somewhere.
+    pub fn move_mouse_to(&mut self, location: Vec2) {
+        self.location = Some(location);
+        self.queue.push(SimulatedEvent::CursorMoved(location));
+    }
+    /// Move the mouse relative to the last simulated location (or the center of the screen if the
+    /// mouse hasn't been moved by simulated input yet).
+    pub fn move_mouse_by(&mut self, motion: Vec2) {
+        let location = self.location.unwrap_or_default() + motion;
+        self.move_mouse_to(location);
+    }
+    /// Scroll the mouse wheel by a number of lines. Positive `y` is scrolling up, positive `x` is
+    /// scrolling right.
+    pub fn scroll(&mut self, x: f32, y: f32) {
+        self.queue.push(SimulatedEvent::MouseWheel(Vec2::new(x, y)));
+    }
+}
+
+/// Send any simulated input to Bevy before Bevy processes the input for this frame
+#[allow(clippy::too_many_arguments)]
+fn send_simulated_input(
+    mut engine: ResMut<Engine>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    mut keyboard_input_events: MessageWriter<KeyboardInput>,
+    mut mouse_button_events: MessageWriter<MouseButtonInput>,
+    mut cursor_moved_events: MessageWriter<CursorMoved>,
+    mut mouse_motion_events: MessageWriter<MouseMotion>,
+    mut mouse_wheel_events: MessageWriter<MouseWheel>,
+    mut last_position: Local<Option<Vec2>>,
+) {
+    // There won't be a window if we're running headless
+    let window = window_query.single().unwrap_or(Entity::PLACEHOLDER);
+    let window_dimensions = engine.window_dimensions;
+    let simulated_input = &mut engine.simulated_input;
+    let events = std::mem::take(&mut simulated_input.queue);
+    simulated_input.queue = std::mem::take(&mut simulated_input.next_queue);
+
+    for event in events {
+        match event {
+            SimulatedEvent::Key(key_code, state) => {
+                keyboard_input_events.write(KeyboardInput {
+                    key_code,
+                    logical_key: Key::Unidentified(NativeKey::Unidentified),
+                    state,
+                    text: None,
+                    repeat: false,
+                    window,
+                });
+            }
+            SimulatedEvent::MouseButton(button, state) => {
+                mouse_button_events.write(MouseButtonInput {
+                    button,
+                    state,
+                    window,
+                });
+            }
+            SimulatedEvent::CursorMoved(location) => {
+                // Convert from our game space to bevy's window space
+                let position = Vec2::new(
+                    location.x + window_dimensions.x * 0.5,
+                    -location.y + window_dimensions.y * 0.5,
+                );
+                let delta = last_position.map(|last| position - last);
+                *last_position = Some(position);
+                cursor_moved_events.write(CursorMoved {
+                    window,
+                    position,
+                    delta,
+                });
+                if let Some(delta) = delta {
+                    mouse_motion_events.write(MouseMotion { delta });
+                }
+            }
+            SimulatedEvent::MouseWheel(scroll) => {
+                mouse_wheel_events.write(MouseWheel {
+                    unit: MouseScrollUnit::Line,
+                    x: scroll.x,
+                    y: scroll.y,
+                    window,
+                });
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::prelude::Engine;
 use bevy::{platform::collections::HashMap, prelude::*};
+use serde::{Deserialize, Serialize};
 
 // Re-export some Bevy types to use
 pub use bevy::input::keyboard::{KeyCode, KeyboardInput};
@@ -96,7 +97,7 @@ impl KeyboardStateChain {
 
 /// Represents the end-state of all keys during the last frame. Access it through
 /// [`Engine.keyboard_state`](crate::prelude::Engine) in your game logic function.
-#[derive(Clone, Debug, Default, Resource)]
+#[derive(Clone, Debug, Default, Deserialize, Resource, Serialize)]
 pub struct KeyboardState {
     this_frame: HashMap<KeyCode, bool>,
     last_frame: HashMap<KeyCode, bool>,
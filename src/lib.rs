@@ -65,13 +65,27 @@
 //! [this `README.md` file](https://github.com/CleanCut/rusty_engine/tree/main/assets/audio/music)
 //! for more information.
 //!
+pub mod animation;
 pub mod audio;
+pub mod error;
 pub mod game;
+pub mod headless;
+pub mod hierarchy;
+pub mod input;
 pub mod keyboard;
 pub mod mouse;
+pub mod particles;
+pub mod path;
 pub mod physics;
+pub mod pixels;
+pub mod recording;
+pub mod scene;
+pub mod shape;
 pub mod sprite;
 pub mod text;
+pub mod tiled;
+pub mod tilemap;
+pub mod tween;
 
 // Public prelude
 pub mod prelude {
@@ -79,11 +93,15 @@ pub mod prelude {
         DOWN, EAST, LEFT, NORTH, NORTH_EAST, NORTH_WEST, RIGHT, SOUTH, SOUTH_EAST, SOUTH_WEST, UP,
         WEST,
     };
-    pub use crate::{audio::*, game::*, keyboard::*, mouse::*, physics::*, sprite::*, text::*};
+    pub use crate::{
+        animation::*, audio::*, error::*, game::*, headless::*, hierarchy::*, input::*,
+        keyboard::*, mouse::*, particles::*, path::*, physics::*, pixels::*, recording::*,
+        scene::*, shape::*, sprite::*, text::*, tiled::*, tilemap::*, tween::*,
+    };
     pub use bevy::ecs as bevy_ecs;
     pub use bevy::{
         self,
-        prelude::{Resource, Time, Timer, TimerMode, Vec2},
+        prelude::{Color, Rect, Resource, Time, Timer, TimerMode, UVec2, Vec2},
     };
 }
 
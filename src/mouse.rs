@@ -2,6 +2,7 @@
 
 use crate::prelude::Engine;
 use bevy::{platform::collections::HashSet, prelude::*};
+use serde::{Deserialize, Serialize};
 
 // Re-export some Bevy types to use
 pub use bevy::{
@@ -101,7 +102,7 @@ impl MouseStateChain {
 /// If you need to process all mouse events that occurred during a single frame, use the
 /// `mouse_button_events`, `mouse_location_events`, `mouse_motion_events`, or `mouse_wheel_events`
 /// fields on [`Engine`](crate::prelude::Engine).
-#[derive(Clone, Debug, Default, Resource)]
+#[derive(Clone, Debug, Default, Deserialize, Resource, Serialize)]
 pub struct MouseState {
     location: Option<Vec2>,
     motion: Vec2,
@@ -114,7 +115,7 @@ pub struct MouseState {
 /// A simplification of mouse wheel events over a frame into a single state. Unless you are treating
 /// the mouse wheel as if scrolling were equivalent to clicking a mouse button, you probably want to
 ///  use [`Engine::mouse_wheel_events`](crate::prelude::Engine::mouse_wheel_events) instead.
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct MouseWheelState {
     /// The y component of the mouse wheel movement. This is the "normal" scrolling direction of a
     /// typical mouse wheel. This will be either `-1.0`, `0.0`, or `1.0`. For fine-grained
@@ -0,0 +1,408 @@
+//! Facilities for particle effects like explosions, smoke, and sparks. A [`ParticleEmitter`] sends
+//! out particles which fly off, fall with gravity, and change color and size until they disappear.
+//! Particles aren't sprites -- you can't look them up or collide with them -- which keeps them
+//! cheap enough to have thousands of them on the screen at once.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! let _ = game.add_sprite("car", SpritePreset::RacingCarRed);
+//! // A trail of smoke that follows the car around
+//! let smoke = game.add_emitter("smoke", ShapeKind::Circle(6.0));
+//! smoke.parent = Some("car".into());
+//! smoke.translation.x = -60.0; // the back of the car
+//! smoke.rotation = LEFT;
+//! smoke.spread = 0.5;
+//! smoke.rate = 30.0;
+//! smoke.start_color = Color::srgba(0.5, 0.5, 0.5, 0.8);
+//! smoke.end_color = Color::srgba(0.5, 0.5, 0.5, 0.0);
+//! smoke.end_scale = 3.0;
+//! game.run(GameState);
+//! # }
+//! ```
+//!
+//! For a one-time effect like an explosion, set `rate` to `0.0` (the default) and call
+//! [`ParticleEmitter::burst`] whenever it should go off.
+
+use crate::{
+    hierarchy::WorldTransform,
+    prelude::{Engine, SpritePreset},
+    shape::ShapeKind,
+    sprite::Sprite,
+};
+use bevy::{
+    asset::RenderAssetUsages,
+    platform::collections::HashMap,
+    prelude::{Sprite as BevySprite, *},
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use std::path::PathBuf;
+
+pub(crate) struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, sync_particles);
+    }
+}
+
+/// What each particle of a [`ParticleEmitter`] looks like
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParticleLook {
+    /// An image file in the `assets/` directory
+    Image(PathBuf),
+    /// A filled shape. Polylines are drawn two pixels wide.
+    Shape(ShapeKind),
+}
+
+impl From<SpritePreset> for ParticleLook {
+    fn from(preset: SpritePreset) -> Self {
+        Self::Image(preset.into())
+    }
+}
+
+impl From<&str> for ParticleLook {
+    fn from(filepath: &str) -> Self {
+        Self::Image(filepath.into())
+    }
+}
+
+impl From<PathBuf> for ParticleLook {
+    fn from(filepath: PathBuf) -> Self {
+        Self::Image(filepath)
+    }
+}
+
+impl From<ShapeKind> for ParticleLook {
+    fn from(kind: ShapeKind) -> Self {
+        Self::Shape(kind)
+    }
+}
+
+/// The default for [`ParticleEmitter::max_particles`]
+const DEFAULT_MAX_PARTICLES: usize = 1000;
+
+/// A [`ParticleEmitter`] sends out particles. Create one with
+/// [`Engine::add_emitter`](crate::prelude::Engine::add_emitter).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParticleEmitter {
+    /// READONLY: A label to identify the emitter. This must be unique among emitters, or the
+    /// emitter will replace the existing emitter with the same label.
+    pub label: String,
+    /// SYNCED: What each particle looks like. Particles that have already been emitted keep the
+    /// look they started with.
+    pub look: ParticleLook,
+    /// SYNCED: Where particles start out in 2D game space (or relative to `parent`)
+    pub translation: Vec2,
+    /// SYNCED: Depth of the particles. 0.0 (back) to 999.0 (front)
+    pub layer: f32,
+    /// SYNCED: The direction particles fly off in, in radians (or relative to `parent`). See
+    /// constants UP, DOWN, LEFT, RIGHT
+    pub rotation: f32,
+    /// SYNCED: The label of the sprite this emitter is attached to, if any. When set,
+    /// `translation` and `rotation` are relative to the parent sprite, so particles are emitted
+    /// from wherever it goes. Particles don't follow the sprite once they've been emitted.
+    pub parent: Option<String>,
+    /// SYNCED: How many particles to emit per second. Defaults to `0.0`, so that particles are only
+    /// emitted by [`ParticleEmitter::burst`].
+    pub rate: f32,
+    /// SYNCED: How many seconds each particle lasts. Defaults to `1.0`.
+    pub lifetime: f32,
+    /// SYNCED: The most particles from this emitter that can be around at once. Particles that
+    /// would go over the limit (from `rate` or a [`burst`](ParticleEmitter::burst)) are never
+    /// emitted. Defaults to `1000`.
+    pub max_particles: usize,
+    /// SYNCED: How fast particles start out, in pixels per second. Defaults to `100.0`.
+    pub speed: f32,
+    /// SYNCED: How much the speed of each particle varies at random, in pixels per second. Each
+    /// particle starts out somewhere between `speed - speed_variation` and
+    /// `speed + speed_variation`. Defaults to `0.0`.
+    pub speed_variation: f32,
+    /// SYNCED: How wide the cone that particles fly off in is, in radians, centered on `rotation`.
+    /// `0.0` sends every particle in the same direction, and the default, [`TAU`](std::f32::consts::TAU),
+    /// sends particles in every direction.
+    pub spread: f32,
+    /// SYNCED: How much particles speed up each second, in pixels per second. Defaults to
+    /// `Vec2::ZERO`. Try `Vec2::new(0.0, -500.0)` to make particles fall.
+    pub gravity: Vec2,
+    /// SYNCED: The color particles start out as. Defaults to [`Color::WHITE`].
+    pub start_color: Color,
+    /// SYNCED: The color particles fade to by the end of their lifetime. Use the alpha channel to
+    /// fade particles out. Defaults to fully transparent white.
+    pub end_color: Color,
+    /// SYNCED: The scale particles start out at. Defaults to `1.0`.
+    pub start_scale: f32,
+    /// SYNCED: The scale particles grow or shrink to by the end of their lifetime. Defaults to
+    /// `1.0`.
+    pub end_scale: f32,
+    /// READONLY: The scene that was active when this emitter was added with
+    /// [`Engine::add_emitter`](crate::prelude::Engine::add_emitter), if any. When that scene is
+    /// exited, this emitter is removed (unless it is `persistent`).
+    pub scene: Option<String>,
+    /// SYNCED: If `true`, this emitter is not removed when the scene it was added in is exited.
+    pub persistent: bool,
+    // particles to emit at the end of the frame, regardless of the rate
+    pub(crate) pending_burst: usize,
+}
+
+impl ParticleEmitter {
+    /// Create an emitter. Usually you want
+    /// [`Engine::add_emitter`](crate::prelude::Engine::add_emitter) instead.
+    pub fn new<T: Into<String>, L: Into<ParticleLook>>(label: T, look: L) -> Self {
+        Self {
+            label: label.into(),
+            look: look.into(),
+            translation: Vec2::ZERO,
+            layer: 0.0,
+            rotation: 0.0,
+            parent: None,
+            rate: 0.0,
+            lifetime: 1.0,
+            max_particles: DEFAULT_MAX_PARTICLES,
+            speed: 100.0,
+            speed_variation: 0.0,
+            spread: std::f32::consts::TAU,
+            gravity: Vec2::ZERO,
+            start_color: Color::WHITE,
+            end_color: Color::WHITE.with_alpha(0.0),
+            start_scale: 1.0,
+            end_scale: 1.0,
+            scene: None,
+            persistent: false,
+            pending_burst: 0,
+        }
+    }
+
+    /// Emit `count` particles all at once, at the end of this frame. Handy for explosions. No more
+    /// than `max_particles` particles are emitted, counting the ones that are already around.
+    pub fn burst(&mut self, count: usize) {
+        self.pending_burst = self.pending_burst.saturating_add(count);
+    }
+}
+
+/// A single particle, along with everything it needs to know to move and change by itself
+#[derive(Component)]
+struct Particle {
+    // the label of the emitter that emitted it
+    emitter: String,
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    gravity: Vec2,
+    start_color: Color,
+    end_color: Color,
+    start_scale: f32,
+    end_scale: f32,
+}
+
+/// A small, fast random number generator (xorshift). Particles don't need anything fancier, and
+/// a fixed seed keeps recorded input replays looking the same.
+struct ParticleRng(u64);
+
+impl Default for ParticleRng {
+    fn default() -> Self {
+        Self(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+impl ParticleRng {
+    /// A random number from `-1.0` to `1.0`
+    fn next_signed(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+}
+
+/// What the particle system keeps track of between frames for each emitter
+#[derive(Default)]
+struct EmitterState {
+    // fractional particles that haven't been emitted yet
+    accumulator: f32,
+    // the image drawn for the emitter's shape, if it has one
+    shape_image: Option<(ShapeKind, Handle<Image>)>,
+}
+
+/// system - move, fade, and despawn particles, and emit new ones
+#[allow(clippy::too_many_arguments)]
+fn sync_particles(
+    mut commands: Commands,
+    mut engine: ResMut<Engine>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    time: Res<Time>,
+    mut rng: Local<ParticleRng>,
+    mut states: Local<HashMap<String, EmitterState>>,
+    sprites: Query<&Sprite>,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform, &mut BevySprite)>,
+) {
+    let delta = time.delta_secs();
+    // how many particles from each emitter are still around
+    let mut alive: HashMap<String, usize> = HashMap::new();
+    for (entity, mut particle, mut transform, mut bevy_sprite) in particles.iter_mut() {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        *alive.entry_ref(particle.emitter.as_str()).or_default() += 1;
+        let gravity = particle.gravity;
+        particle.velocity += gravity * delta;
+        transform.translation += (particle.velocity * delta).extend(0.0);
+        let t = particle.age / particle.lifetime;
+        bevy_sprite.color = particle.start_color.mix(&particle.end_color, t);
+        transform.scale =
+            Vec3::splat(particle.start_scale + (particle.end_scale - particle.start_scale) * t);
+    }
+
+    let engine = &mut *engine;
+    states.retain(|label, _| engine.emitters.contains_key(label));
+    // Sprites have been handed over to Bevy by now, so look up parents there
+    let by_label: HashMap<&str, &Sprite> = if engine
+        .emitters
+        .values()
+        .any(|emitter| emitter.parent.is_some())
+    {
+        sprites
+            .iter()
+            .map(|sprite| (sprite.label.as_str(), sprite))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    for emitter in engine.emitters.values_mut() {
+        let state = states.entry(emitter.label.clone()).or_default();
+        let mut count = std::mem::take(&mut emitter.pending_burst);
+        state.accumulator += emitter.rate.max(0.0) * delta;
+        if state.accumulator >= 1.0 {
+            count = count.saturating_add(state.accumulator as usize);
+            state.accumulator = state.accumulator.fract();
+        }
+        let alive = alive.get(&emitter.label).copied().unwrap_or(0);
+        count = count.min(emitter.max_particles.saturating_sub(alive));
+        if count == 0 {
+            continue;
+        }
+        let image = match &emitter.look {
+            ParticleLook::Image(filepath) => asset_server.load(filepath.clone()),
+            ParticleLook::Shape(kind) => match &state.shape_image {
+                Some((image_kind, image)) if image_kind == kind => image.clone(),
+                _ => {
+                    let image = images.add(shape_image(kind));
+                    state.shape_image = Some((kind.clone(), image.clone()));
+                    image
+                }
+            },
+        };
+        let origin = WorldTransform {
+            translation: emitter.translation,
+            rotation: emitter.rotation,
+            scale: Vec2::ONE,
+        }
+        .resolve(emitter.parent.as_deref(), |label| {
+            by_label.get(label).copied()
+        });
+        for _ in 0..count {
+            let direction = origin.rotation + rng.next_signed() * emitter.spread * 0.5;
+            let speed = emitter.speed + rng.next_signed() * emitter.speed_variation;
+            let mut transform =
+                Transform::from_translation(origin.translation.extend(emitter.layer));
+            transform.scale = Vec3::splat(emitter.start_scale);
+            commands.spawn((
+                Particle {
+                    emitter: emitter.label.clone(),
+                    velocity: Vec2::from_angle(direction) * speed,
+                    age: 0.0,
+                    lifetime: emitter.lifetime,
+                    gravity: emitter.gravity,
+                    start_color: emitter.start_color,
+                    end_color: emitter.end_color,
+                    start_scale: emitter.start_scale,
+                    end_scale: emitter.end_scale,
+                },
+                BevySprite {
+                    image: image.clone(),
+                    color: emitter.start_color,
+                    ..Default::default()
+                },
+                transform,
+            ));
+        }
+    }
+}
+
+/// Draw a white, filled shape into an image, centered on the shape's origin. Edges are smoothed by
+/// sampling each pixel four times.
+fn shape_image(kind: &ShapeKind) -> Image {
+    const POLYLINE_WIDTH: f32 = 2.0;
+    let half_extents = match kind {
+        ShapeKind::Rectangle(size) => size.abs() * 0.5,
+        ShapeKind::Circle(radius) => Vec2::splat(radius.abs()),
+        ShapeKind::Polygon(points) => points.iter().fold(Vec2::ZERO, |max, p| max.max(p.abs())),
+        ShapeKind::Polyline(points) => points
+            .iter()
+            .fold(Vec2::ZERO, |max, p| max.max(p.abs() + POLYLINE_WIDTH * 0.5)),
+    };
+    let size = (half_extents * 2.0).ceil().as_uvec2().max(UVec2::ONE);
+    let inside = |point: Vec2| match kind {
+        ShapeKind::Rectangle(_) => point.abs().cmple(half_extents).all(),
+        ShapeKind::Circle(radius) => point.length() <= *radius,
+        ShapeKind::Polygon(points) => {
+            // Count how many edges a ray to the right crosses
+            let mut crossings = 0;
+            for (i, a) in points.iter().enumerate() {
+                let b = points[(i + 1) % points.len()];
+                if (a.y > point.y) != (b.y > point.y)
+                    && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+                {
+                    crossings += 1;
+                }
+            }
+            crossings % 2 == 1
+        }
+        ShapeKind::Polyline(points) => points.windows(2).any(|segment| {
+            let (a, b) = (segment[0], segment[1]);
+            let t = ((point - a).dot(b - a) / (b - a).length_squared().max(f32::EPSILON))
+                .clamp(0.0, 1.0);
+            point.distance(a + (b - a) * t) <= POLYLINE_WIDTH * 0.5
+        }),
+    };
+    const SAMPLES: [Vec2; 4] = [
+        Vec2::new(0.25, 0.25),
+        Vec2::new(0.75, 0.25),
+        Vec2::new(0.25, 0.75),
+        Vec2::new(0.75, 0.75),
+    ];
+    let top_left = Vec2::new(-(size.x as f32), size.y as f32) * 0.5;
+    let mut data = Vec::with_capacity((size.x * size.y * 4) as usize);
+    for row in 0..size.y {
+        for column in 0..size.x {
+            let covered = SAMPLES
+                .iter()
+                .filter(|sample| {
+                    let offset = Vec2::new(column as f32, row as f32) + **sample;
+                    inside(top_left + offset * Vec2::new(1.0, -1.0))
+                })
+                .count();
+            let alpha = (covered * 255 / SAMPLES.len()) as u8;
+            data.extend_from_slice(&[255, 255, 255, alpha]);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
@@ -0,0 +1,427 @@
+//! Facilities for making sprites follow a route on their own. Give a sprite a [`SpritePath`] -- a
+//! list of waypoints joined by straight lines or smooth curves -- and the engine moves it along at
+//! a steady speed, generating a [`PathEvent`] each time it reaches a waypoint.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! let car = game.add_sprite("car", SpritePreset::RacingCarRed);
+//! // Drive around a smooth loop through four points, forever
+//! let path = car.follow_path(SpritePath::new(
+//!     PathKind::CatmullRom,
+//!     vec![
+//!         Vec2::new(-300.0, -200.0),
+//!         Vec2::new(300.0, -200.0),
+//!         Vec2::new(300.0, 200.0),
+//!         Vec2::new(-300.0, 200.0),
+//!     ],
+//!     250.0,
+//! ));
+//! path.mode = PathMode::Loop;
+//! path.face_direction = true;
+//! game.run(GameState);
+//! # }
+//! ```
+
+use crate::prelude::Engine;
+use bevy::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+use std::{fmt, sync::Arc};
+
+/// How the waypoints of a [`SpritePath`] are joined together
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum PathKind {
+    /// Straight lines from each waypoint to the next
+    #[default]
+    Lines,
+    /// A smooth Catmull-Rom spline that passes through every waypoint
+    CatmullRom,
+    /// Cubic Bezier curves. The points go waypoint, control point, control point, waypoint,
+    /// control point, control point, waypoint, and so on. Leftover points at the end are ignored.
+    Bezier,
+}
+
+/// What a sprite does when it reaches the end of its [`SpritePath`]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum PathMode {
+    /// Stop at the last waypoint
+    #[default]
+    Once,
+    /// Go back around to the first waypoint, and keep going. [`PathKind::Lines`] and
+    /// [`PathKind::CatmullRom`] paths are joined back to the first waypoint automatically. A
+    /// [`PathKind::Bezier`] path jumps back to its first waypoint, so end it where it starts.
+    Loop,
+    /// Turn around at each end, and go back the way the sprite came
+    PingPong,
+}
+
+/// A [`SpritePath`] is a route for a sprite to follow on its own. Set it with
+/// [`Sprite::follow_path`](crate::prelude::Sprite::follow_path) or the
+/// [`Sprite::path`](crate::prelude::Sprite::path) field. Each frame, before the logic functions
+/// run, the engine moves the sprite's `translation` along the path at `speed` pixels per second.
+/// For an attached sprite (see [`Sprite::parent`](crate::prelude::Sprite::parent)), the points
+/// are relative to the parent.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SpritePath {
+    /// SYNCED: How the points are joined together
+    pub kind: PathKind,
+    /// SYNCED: The points to go through, in order. See [`PathKind`] for what they mean.
+    pub points: Vec<Vec2>,
+    /// SYNCED: How fast to go, in pixels per second
+    pub speed: f32,
+    /// SYNCED: What to do at the end of the path. Defaults to [`PathMode::Once`].
+    #[serde(default)]
+    pub mode: PathMode,
+    /// SYNCED: If `true`, the sprite's `rotation` is set so that it faces the direction it is
+    /// going. Defaults to `false`.
+    #[serde(default)]
+    pub face_direction: bool,
+    /// SYNCED: If `true`, the sprite stays where it is on the path. While paused, the path leaves
+    /// the sprite's `translation` and `rotation` alone, so logic or `velocity` can move it.
+    /// Defaults to `false`.
+    #[serde(default)]
+    pub paused: bool,
+    /// SYNCED: How far along the path the sprite is, in pixels from the first waypoint. Set this
+    /// to `0.0` to start over.
+    #[serde(default)]
+    pub distance: f32,
+    /// SYNCED: Whether a [`PathMode::PingPong`] path is being followed backwards
+    #[serde(default)]
+    pub reversed: bool,
+    /// READONLY: Whether a [`PathMode::Once`] path has reached its end. A finished path no longer
+    /// changes the sprite's `translation` or `rotation`.
+    #[serde(default)]
+    pub finished: bool,
+    // the shape of the path, worked out the last time it moved a sprite
+    #[serde(skip)]
+    cache: TraceCache,
+}
+
+impl SpritePath {
+    /// Create a path through `points`, joined together according to `kind`, followed at `speed`
+    /// pixels per second
+    pub fn new(kind: PathKind, points: Vec<Vec2>, speed: f32) -> Self {
+        Self {
+            kind,
+            points,
+            speed,
+            mode: PathMode::default(),
+            face_direction: false,
+            paused: false,
+            distance: 0.0,
+            reversed: false,
+            finished: false,
+            cache: TraceCache::default(),
+        }
+    }
+
+    /// The number of waypoints, which is the number of points unless this is a
+    /// [`PathKind::Bezier`] path
+    pub fn waypoint_count(&self) -> usize {
+        match self.kind {
+            PathKind::Lines | PathKind::CatmullRom => self.points.len(),
+            PathKind::Bezier => self.points.len().div_ceil(3),
+        }
+    }
+
+    /// The length of the whole path, in pixels
+    pub fn length(&self) -> f32 {
+        self.trace().length()
+    }
+
+    /// The spot `distance` pixels along the path from the first waypoint, or `None` if there are
+    /// no points. Handy for drawing the path, or for placing things along it.
+    pub fn position_at(&self, distance: f32) -> Option<Vec2> {
+        let trace = self.trace();
+        if trace.samples.is_empty() {
+            return None;
+        }
+        Some(trace.locate(distance.clamp(0.0, trace.length())).0)
+    }
+
+    /// Whether the path joins back up with the first waypoint, rather than jumping back to it
+    fn closed(&self) -> bool {
+        self.mode == PathMode::Loop && self.kind != PathKind::Bezier
+    }
+
+    /// The shape of the path, from the cache if the path hasn't changed since it was cached
+    fn trace(&self) -> Arc<Trace> {
+        match &self.cache.0 {
+            Some(cached) if cached.matches(self) => cached.trace.clone(),
+            _ => Arc::new(self.build_trace()),
+        }
+    }
+
+    /// Same as [`trace`](SpritePath::trace), but caches a newly worked out shape for next time
+    fn cached_trace(&mut self) -> Arc<Trace> {
+        if let Some(cached) = &self.cache.0
+            && cached.matches(self)
+        {
+            return cached.trace.clone();
+        }
+        let trace = Arc::new(self.build_trace());
+        self.cache.0 = Some(CachedTrace {
+            kind: self.kind,
+            closed: self.closed(),
+            points: self.points.clone(),
+            trace: trace.clone(),
+        });
+        trace
+    }
+
+    /// Work out the shape of the path as lots of short straight lines
+    fn build_trace(&self) -> Trace {
+        let closed = self.closed();
+        let points = &self.points;
+        let count = points.len();
+        // Each segment goes from one waypoint to the next
+        let segments = match self.kind {
+            PathKind::Lines | PathKind::CatmullRom if closed && count > 1 => count,
+            PathKind::Lines | PathKind::CatmullRom => count.saturating_sub(1),
+            PathKind::Bezier => count.saturating_sub(1) / 3,
+        };
+        let point = |index: isize| {
+            if closed {
+                points[index.rem_euclid(count as isize) as usize]
+            } else {
+                points[index.clamp(0, count as isize - 1) as usize]
+            }
+        };
+        let mut trace = Trace {
+            samples: Vec::new(),
+            waypoints: Vec::new(),
+        };
+        if count == 0 {
+            return trace;
+        }
+        let mut previous = (points[0], 0.0);
+        trace.samples.push(previous);
+        trace.waypoints.push(0.0);
+        // Straight lines don't need breaking up
+        let samples = match self.kind {
+            PathKind::Lines => 1,
+            PathKind::CatmullRom | PathKind::Bezier => CURVE_SAMPLES,
+        };
+        for segment in 0..segments {
+            let i = segment as isize;
+            for sample in 1..=samples {
+                let t = sample as f32 / samples as f32;
+                let position = match self.kind {
+                    PathKind::Lines => point(i).lerp(point(i + 1), t),
+                    PathKind::CatmullRom => {
+                        catmull_rom(point(i - 1), point(i), point(i + 1), point(i + 2), t)
+                    }
+                    PathKind::Bezier => {
+                        let start = i * 3;
+                        bezier(
+                            point(start),
+                            point(start + 1),
+                            point(start + 2),
+                            point(start + 3),
+                            t,
+                        )
+                    }
+                };
+                let (last, distance) = previous;
+                previous = (position, distance + last.distance(position));
+                trace.samples.push(previous);
+            }
+            trace.waypoints.push(trace.length());
+        }
+        trace
+    }
+}
+
+// How many straight lines each curved segment is broken into
+const CURVE_SAMPLES: usize = 16;
+
+fn catmull_rom(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, t: f32) -> Vec2 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+fn bezier(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, t: f32) -> Vec2 {
+    let u = 1.0 - t;
+    p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+}
+
+/// A path's [`Trace`], along with what it was worked out from
+#[derive(Clone)]
+struct CachedTrace {
+    kind: PathKind,
+    closed: bool,
+    points: Vec<Vec2>,
+    trace: Arc<Trace>,
+}
+
+impl CachedTrace {
+    /// Whether the trace still has the shape of the path
+    fn matches(&self, path: &SpritePath) -> bool {
+        self.kind == path.kind && self.closed == path.closed() && self.points == path.points
+    }
+}
+
+/// The cached shape of a [`SpritePath`]. It is shared between copies of the path, and left out
+/// when paths are compared, printed, or saved.
+#[derive(Clone, Default)]
+struct TraceCache(Option<CachedTrace>);
+
+impl PartialEq for TraceCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for TraceCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TraceCache")
+    }
+}
+
+/// A path broken into short straight lines
+struct Trace {
+    // each point along the path, and how far along the path it is
+    samples: Vec<(Vec2, f32)>,
+    // how far along the path each waypoint is. For a closed loop, the last one is the first
+    // waypoint again.
+    waypoints: Vec<f32>,
+}
+
+impl Trace {
+    fn length(&self) -> f32 {
+        self.samples.last().map_or(0.0, |&(_, distance)| distance)
+    }
+
+    /// Where on the path `distance` is, and which way the path is going there
+    fn locate(&self, distance: f32) -> (Vec2, Vec2) {
+        if self.samples.len() < 2 {
+            return (self.samples[0].0, Vec2::ZERO);
+        }
+        let index = self
+            .samples
+            .partition_point(|&(_, sample_distance)| sample_distance < distance)
+            .clamp(1, self.samples.len() - 1);
+        let (a, a_distance) = self.samples[index - 1];
+        let (b, b_distance) = self.samples[index];
+        let amount = if b_distance > a_distance {
+            (distance - a_distance) / (b_distance - a_distance)
+        } else {
+            0.0
+        };
+        (a.lerp(b, amount), b - a)
+    }
+}
+
+/// A [`PathEvent`] is generated when a sprite following a [`SpritePath`] reaches a waypoint. See
+/// [`Engine::path_events`](crate::prelude::Engine::path_events).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathEvent {
+    /// The label of the sprite that reached the waypoint
+    pub sprite_label: String,
+    /// Which waypoint was reached, starting at `0`. See [`SpritePath::waypoint_count`].
+    pub waypoint: usize,
+    /// `true` if this is the last waypoint of a [`PathMode::Once`] path, so the sprite stopped
+    pub finished: bool,
+}
+
+// Most laps of the path a sprite can make in one frame, so tiny paths can't loop forever
+const MAX_LAPS_PER_FRAME: usize = 8;
+
+/// Move all the sprites that have a path along by `engine.delta_f32`, generating events for the
+/// waypoints they reach. Called each frame before the logic functions run.
+pub(crate) fn advance_paths(engine: &mut Engine) {
+    engine.path_events.clear();
+    let delta = engine.delta_f32;
+    for sprite in engine.sprites.values_mut() {
+        let Some(path) = sprite.path.as_mut() else {
+            continue;
+        };
+        // Paused and finished paths leave the sprite alone
+        if path.paused || path.finished {
+            continue;
+        }
+        let trace = path.cached_trace();
+        let length = trace.length();
+        if trace.samples.is_empty() {
+            continue;
+        }
+        let waypoint_count = path.waypoint_count();
+        let mut reached = |waypoint: usize, finished: bool| {
+            engine.path_events.push(PathEvent {
+                sprite_label: sprite.label.clone(),
+                // The end of a closed loop is the first waypoint again
+                waypoint: waypoint % waypoint_count.max(1),
+                finished,
+            });
+        };
+        if length > 0.0 {
+            let mut remaining = path.speed.max(0.0) * delta;
+            for _ in 0..MAX_LAPS_PER_FRAME * 2 {
+                if path.reversed {
+                    let end = (path.distance - remaining).max(0.0);
+                    for (waypoint, &at) in trace.waypoints.iter().enumerate().rev() {
+                        if at >= end && at < path.distance {
+                            reached(waypoint, false);
+                        }
+                    }
+                    remaining -= path.distance - end;
+                    path.distance = end;
+                    if path.distance > 0.0 {
+                        break;
+                    }
+                    // Back at the start
+                    path.reversed = false;
+                } else {
+                    let end = (path.distance + remaining).min(length);
+                    for (waypoint, &at) in trace.waypoints.iter().enumerate() {
+                        if at > path.distance && at <= end {
+                            reached(
+                                waypoint,
+                                path.mode == PathMode::Once
+                                    && waypoint + 1 == trace.waypoints.len(),
+                            );
+                        }
+                    }
+                    remaining -= end - path.distance;
+                    path.distance = end;
+                    if path.distance < length {
+                        break;
+                    }
+                    // At the end
+                    match path.mode {
+                        PathMode::Once => {
+                            path.finished = true;
+                            break;
+                        }
+                        PathMode::Loop => {
+                            path.distance = 0.0;
+                            // A path that isn't closed jumps back to the first waypoint
+                            if !path.closed() {
+                                reached(0, false);
+                            }
+                        }
+                        PathMode::PingPong => path.reversed = true,
+                    }
+                }
+                if remaining <= 0.0 {
+                    break;
+                }
+            }
+        }
+        let (position, direction) = trace.locate(path.distance.clamp(0.0, length));
+        sprite.translation = position;
+        if path.face_direction && direction != Vec2::ZERO {
+            let direction = if path.reversed { -direction } else { direction };
+            sprite.rotation = direction.to_angle();
+        }
+    }
+}
@@ -1,10 +1,13 @@
 //! Rusty Engine's custom collision detection implementation.
 
-use crate::sprite::Sprite;
+use crate::{
+    game::Engine, hierarchy::world_sprite, shape::Shape, sprite::Sprite, tilemap::Tilemap,
+};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashSet,
+    borrow::Cow,
+    collections::{BTreeSet, HashMap},
     f32::consts::{PI, TAU},
     hash::Hash,
 };
@@ -14,6 +17,7 @@ pub(crate) struct PhysicsPlugin;
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<CollisionEvent>()
+            .init_resource::<ExistingCollisions>()
             .add_systems(Update, collision_detection);
     }
 }
@@ -29,6 +33,59 @@ impl Plugin for PhysicsPlugin {
 pub struct CollisionEvent {
     pub state: CollisionState,
     pub pair: CollisionPair,
+    /// The [`tags`](crate::prelude::Sprite::tags) of the two sprites in `pair`, in the same order,
+    /// as they were when the collision began. Tilemaps and shapes have no tags.
+    pub tags: CollisionTags,
+    /// Whether each side of `pair` is a [`Shape`](crate::prelude::Shape), in the same order. A
+    /// shape may have the same label as a sprite, so this tells the two apart.
+    pub shapes: (bool, bool),
+}
+
+/// The tags of the two sprites in a [`CollisionPair`], in the same order
+pub type CollisionTags = (BTreeSet<String>, BTreeSet<String>);
+
+impl CollisionEvent {
+    /// Whether either side of the collision has the tag
+    pub fn either_has_tag(&self, tag: &str) -> bool {
+        self.tags.0.contains(tag) || self.tags.1.contains(tag)
+    }
+
+    /// The label of the side of the collision that has the tag, if any. If both sides have it, the
+    /// first label in `pair` is returned.
+    pub fn label_with_tag(&self, tag: &str) -> Option<&str> {
+        if self.tags.0.contains(tag) {
+            Some(&self.pair.0)
+        } else if self.tags.1.contains(tag) {
+            Some(&self.pair.1)
+        } else {
+            None
+        }
+    }
+
+    /// If one side of the collision has `tag_a` and the other side has `tag_b`, return their
+    /// labels in that order. This makes it easy to handle, say, bullets hitting enemies no matter
+    /// which order the pair is in:
+    ///
+    /// ```rust,no_run
+    /// # use rusty_engine::prelude::*;
+    /// # fn logic(engine: &mut Engine) {
+    /// for event in engine.collision_events.drain(..) {
+    ///     if let Some((bullet, enemy)) = event.tagged("bullet", "enemy") {
+    ///         engine.sprites.remove(bullet);
+    ///         engine.sprites.remove(enemy);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn tagged(&self, tag_a: &str, tag_b: &str) -> Option<(&str, &str)> {
+        if self.tags.0.contains(tag_a) && self.tags.1.contains(tag_b) {
+            Some((&self.pair.0, &self.pair.1))
+        } else if self.tags.1.contains(tag_a) && self.tags.0.contains(tag_b) {
+            Some((&self.pair.1, &self.pair.0))
+        } else {
+            None
+        }
+    }
 }
 
 /// Indicates whether a [`CollisionEvent`] is at the beginning or ending of a collision.
@@ -120,53 +177,131 @@ impl Hash for CollisionPair {
     }
 }
 
+/// The tags of both sides of a collision, and whether each side is a shape
+type CollisionSides = (CollisionTags, (bool, bool));
+
+/// The collisions that are currently ongoing (along with the tags of the sprites when they began),
+/// so we can tell when collisions begin and end. This is a resource (rather than local to the
+/// collision detection system) so that collisions can also be detected in between the steps of
+/// fixed logic functions.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct ExistingCollisions(HashMap<CollisionPair, CollisionSides>);
+
 /// system - detect collisions and generate the collision events
 fn collision_detection(
-    mut existing_collisions: Local<HashSet<CollisionPair>>,
+    mut existing_collisions: ResMut<ExistingCollisions>,
     mut collision_events: MessageWriter<CollisionEvent>,
     query: Query<&Sprite>,
+    engine: Res<Engine>,
+    time: Res<Time<Virtual>>,
 ) {
-    let mut current_collisions = HashSet::<CollisionPair>::new();
-    'outer: for sprite1 in query.iter().filter(|a| a.collision) {
-        for sprite2 in query.iter().filter(|a| a.collision) {
-            if sprite1.label == sprite2.label {
-                // We only need to compare one half of the matrix triangle
-                continue 'outer;
-            }
+    // Collisions are frozen while the game is paused
+    if time.is_paused() {
+        return;
+    }
+    collision_events.write_batch(detect_collisions(
+        query.iter(),
+        engine.tilemaps.values(),
+        engine.shapes.values(),
+        &mut existing_collisions,
+    ));
+}
+
+/// Detect collisions between the sprites and shapes (and between them and the solid tiles of the
+/// tilemaps), update the existing collisions, and return the collision events for any collisions
+/// which began or ended.
+pub(crate) fn detect_collisions<'a>(
+    sprites: impl Iterator<Item = &'a Sprite>,
+    tilemaps: impl Iterator<Item = &'a Tilemap>,
+    shapes: impl Iterator<Item = &'a Shape>,
+    existing_collisions: &mut ExistingCollisions,
+) -> Vec<CollisionEvent> {
+    let existing_collisions = &mut existing_collisions.0;
+    let all_sprites: Vec<&Sprite> = sprites.collect();
+    // Sprites attached to a parent collide wherever they are in world space
+    let by_label: HashMap<&str, &Sprite> = all_sprites
+        .iter()
+        .map(|sprite| (sprite.label.as_str(), *sprite))
+        .collect();
+    // Each sprite, and whether it is really a shape
+    let sprites: Vec<(Cow<Sprite>, bool)> = all_sprites
+        .iter()
+        .filter(|a| a.collides())
+        .map(|sprite| {
+            let sprite = world_sprite(sprite, |label| by_label.get(label).copied());
+            (sprite, false)
+        })
+        // Shapes collide just like a sprite with the same placement and collider would
+        .chain(
+            shapes
+                .filter(|shape| shape.collides())
+                .map(|shape| (Cow::Owned(shape.collision_sprite()), true)),
+        )
+        .collect();
+    let mut current_collisions = HashMap::<CollisionPair, CollisionSides>::new();
+    for (i, (sprite1, shape1)) in sprites.iter().enumerate() {
+        // We only need to compare one half of the matrix triangle. This goes by position rather
+        // than by label, since a shape may have the same label as a sprite.
+        for (sprite2, shape2) in sprites[..i].iter() {
             if Collider::colliding(sprite1, sprite2) {
-                current_collisions
-                    .insert(CollisionPair(sprite1.label.clone(), sprite2.label.clone()));
+                current_collisions.insert(
+                    CollisionPair(sprite1.label.clone(), sprite2.label.clone()),
+                    (
+                        (sprite1.tags.clone(), sprite2.tags.clone()),
+                        (*shape1, *shape2),
+                    ),
+                );
+            }
+        }
+    }
+    let tilemaps: Vec<&Tilemap> = tilemaps.filter(|tilemap| tilemap.collision).collect();
+    if !tilemaps.is_empty() {
+        for (sprite, shape) in sprites.iter() {
+            let poly = sprite.collider.relative_to(sprite);
+            for tilemap in tilemaps.iter() {
+                if tilemap.colliding(&poly) {
+                    current_collisions.insert(
+                        CollisionPair(sprite.label.clone(), tilemap.label.clone()),
+                        ((sprite.tags.clone(), BTreeSet::new()), (*shape, false)),
+                    );
+                }
             }
         }
     }
 
-    let beginning_collisions: Vec<_> = current_collisions
-        .difference(&existing_collisions)
-        .cloned()
-        .collect();
-
-    collision_events.write_batch(beginning_collisions.iter().map(|p| CollisionEvent {
-        state: CollisionState::Begin,
-        pair: p.clone(),
-    }));
-
-    for beginning_collision in beginning_collisions {
-        existing_collisions.insert(beginning_collision);
+    // The tags of an ongoing collision stay as they were when it began, so that the end of a
+    // collision can be matched up with its beginning even if the sprite is gone by then
+    let mut collision_events: Vec<_> = Vec::new();
+    for (pair, sides) in current_collisions.iter() {
+        if !existing_collisions.contains_key(pair) {
+            let (tags, shapes) = sides.clone();
+            collision_events.push(CollisionEvent {
+                state: CollisionState::Begin,
+                pair: pair.clone(),
+                tags,
+                shapes,
+            });
+            existing_collisions.insert(pair.clone(), sides.clone());
+        }
     }
 
     let ending_collisions: Vec<_> = existing_collisions
-        .difference(&current_collisions)
+        .keys()
+        .filter(|pair| !current_collisions.contains_key(*pair))
         .cloned()
         .collect();
-
-    collision_events.write_batch(ending_collisions.iter().map(|p| CollisionEvent {
-        state: CollisionState::End,
-        pair: p.clone(),
-    }));
-
     for ending_collision in ending_collisions {
-        let _ = existing_collisions.remove(&ending_collision);
+        if let Some((pair, (tags, shapes))) = existing_collisions.remove_entry(&ending_collision) {
+            collision_events.push(CollisionEvent {
+                state: CollisionState::End,
+                pair,
+                tags,
+                shapes,
+            });
+        }
     }
+
+    collision_events
 }
 
 /// Represents the collider (or lack thereof) of a sprite. Two sprites need to have colliders AND
@@ -304,14 +439,39 @@ impl Collider {
         }
         rotated_points
     }
+    /// Return the points moved by an offset
+    fn translated(&self, offset: Vec2) -> Self {
+        match self {
+            Self::NoCollider => Self::NoCollider,
+            Self::Poly(points) => Self::Poly(points.iter().map(|&point| point + offset).collect()),
+        }
+    }
+    /// Return the points scaled (and flipped) separately along the x and y axes
+    fn scaled(&self, scale: Vec2) -> Self {
+        match self {
+            Self::NoCollider => Self::NoCollider,
+            Self::Poly(points) => Self::Poly(points.iter().map(|&point| point * scale).collect()),
+        }
+    }
     #[doc(hidden)]
-    /// Used internally to scale colliders to match a sprite's current translation, rotation, and scale
+    /// Used internally to scale colliders to match a sprite's current anchor, translation,
+    /// rotation, scale, and flipping
     pub fn relative_to(&self, sprite: &Sprite) -> Vec<Vec2> {
-        self.rotated(sprite.rotation)
+        self.translated(-sprite.anchor_offset())
+            .scaled(sprite.flipped_scale())
+            .rotated(sprite.rotation)
             .iter()
-            .map(|&v| v * sprite.scale + sprite.translation) // scale & translation
+            .map(|&v| v + sprite.translation) // translation
             .collect()
     }
+    #[doc(hidden)]
+    /// Used internally to anchor, scale and flip collider lines to match a sprite, leaving rotation
+    /// and translation to the transform
+    pub fn scaled_points(&self, sprite: &Sprite) -> Vec<Vec2> {
+        self.translated(-sprite.anchor_offset())
+            .scaled(sprite.flipped_scale())
+            .points()
+    }
     /// Returns a `Vec<Vec2>` containing the points of the collider, or an empty `Vec` if there is
     /// no collider.
     pub fn points(&self) -> Vec<Vec2> {
@@ -334,44 +494,97 @@ impl Collider {
         if sprite1.collider.is_poly() && sprite2.collider.is_poly() {
             let poly1 = sprite1.collider.relative_to(sprite1);
             let poly2 = sprite2.collider.relative_to(sprite2);
-            // Polygon intersection algorithm adapted from
-            // https://stackoverflow.com/questions/10962379/how-to-check-intersection-between-2-rotated-rectangles
-            for poly in [poly1.clone(), poly2.clone()] {
-                for (idx, &p1) in poly.iter().enumerate() {
-                    let p2 = poly[(idx + 1) % poly.len()];
-                    let normal = Vec2::new(p2.y - p1.y, p1.x - p2.x);
-
-                    let mut min_a = None;
-                    let mut max_a = None;
-                    for &p in poly1.iter() {
-                        let projected = normal.x * p.x + normal.y * p.y;
-                        if min_a.is_none() || projected < min_a.unwrap() {
-                            min_a = Some(projected);
-                        }
-                        if max_a.is_none() || projected > max_a.unwrap() {
-                            max_a = Some(projected);
-                        }
-                    }
+            return polygons_colliding(&poly1, &poly2);
+        }
+        false
+    }
+}
 
-                    let mut min_b = None;
-                    let mut max_b = None;
-                    for &p in poly2.iter() {
-                        let projected = normal.x * p.x + normal.y * p.y;
-                        if min_b.is_none() || projected < min_b.unwrap() {
-                            min_b = Some(projected);
-                        }
-                        if max_b.is_none() || projected > max_b.unwrap() {
-                            max_b = Some(projected);
-                        }
-                    }
+/// A polygon collider with the outline `points`, or [`Collider::NoCollider`] if there are fewer
+/// than three points. Collision detection only works with convex polygons, so a concave outline is
+/// replaced with its convex hull. If `name` is given, that is logged as a warning about `name`.
+pub(crate) fn convex_collider(points: Vec<Vec2>, name: Option<&str>) -> Collider {
+    if points.len() < 3 {
+        return Collider::NoCollider;
+    }
+    let collider = Collider::poly(&points);
+    if collider.is_convex() {
+        return collider;
+    }
+    if let Some(name) = name {
+        warn!("{name} isn't convex, so its convex hull is used for collisions");
+    }
+    let hull = convex_hull(points);
+    if hull.len() < 3 {
+        Collider::NoCollider
+    } else {
+        Collider::Poly(hull)
+    }
+}
 
-                    if max_a < min_b || max_b < min_a {
-                        return false;
-                    }
+/// The convex hull of some points, counterclockwise, using Andrew's monotone chain algorithm
+fn convex_hull(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    // Each half of the hull only keeps points where it turns left
+    let half_hull = |points: &mut dyn Iterator<Item = Vec2>| {
+        let mut hull: Vec<Vec2> = Vec::new();
+        for point in points {
+            while let [.., a, b] = hull[..]
+                && (b - a).perp_dot(point - b) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        hull.pop();
+        hull
+    };
+    let mut hull = half_hull(&mut points.iter().copied());
+    hull.extend(half_hull(&mut points.iter().rev().copied()));
+    hull
+}
+
+/// Whether two convex polygons in world space overlap
+pub(crate) fn polygons_colliding(poly1: &[Vec2], poly2: &[Vec2]) -> bool {
+    // Polygon intersection algorithm adapted from
+    // https://stackoverflow.com/questions/10962379/how-to-check-intersection-between-2-rotated-rectangles
+    for poly in [poly1, poly2] {
+        for (idx, &p1) in poly.iter().enumerate() {
+            let p2 = poly[(idx + 1) % poly.len()];
+            let normal = Vec2::new(p2.y - p1.y, p1.x - p2.x);
+
+            let mut min_a = None;
+            let mut max_a = None;
+            for &p in poly1.iter() {
+                let projected = normal.x * p.x + normal.y * p.y;
+                if min_a.is_none() || projected < min_a.unwrap() {
+                    min_a = Some(projected);
                 }
+                if max_a.is_none() || projected > max_a.unwrap() {
+                    max_a = Some(projected);
+                }
+            }
+
+            let mut min_b = None;
+            let mut max_b = None;
+            for &p in poly2.iter() {
+                let projected = normal.x * p.x + normal.y * p.y;
+                if min_b.is_none() || projected < min_b.unwrap() {
+                    min_b = Some(projected);
+                }
+                if max_b.is_none() || projected > max_b.unwrap() {
+                    max_b = Some(projected);
+                }
+            }
+
+            if max_a < min_b || max_b < min_a {
+                return false;
             }
-            return true;
         }
-        false
     }
+    true
 }
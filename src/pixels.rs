@@ -0,0 +1,234 @@
+//! Facilities for drawing your own images pixel by pixel, instead of loading them from files. A
+//! [`PixelImage`] is a grid of colors that you can change whenever you like, which makes it handy
+//! for procedural terrain, heatmaps, Game of Life boards, and minimaps.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! let board = game.add_pixel_image("board", PixelImage::new(64, 64));
+//! board.fill(Color::BLACK);
+//! board.set_pixel(32, 32, Color::WHITE);
+//! // Show the image with a sprite, scaled up so each pixel is easy to see
+//! let sprite = game.add_pixel_sprite("board sprite", "board");
+//! sprite.scale = 8.0;
+//! game.run(GameState);
+//! # }
+//! ```
+//!
+//! Change the pixels from a logic function through
+//! [`Engine::pixel_images`](crate::prelude::Engine::pixel_images), and every sprite showing the
+//! image is updated at the end of the frame.
+
+use crate::{error::EngineError, prelude::Engine, sprite::Sprite};
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    platform::collections::{HashMap, HashSet},
+    prelude::{Sprite as BevySprite, *},
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+pub(crate) struct PixelImagePlugin;
+
+impl Plugin for PixelImagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            sync_pixel_images.after(crate::animation::show_animation_frames),
+        );
+    }
+}
+
+/// A [`PixelImage`] is an image made of RGBA pixels in memory. Add it to the game with
+/// [`Engine::add_pixel_image`](crate::prelude::Engine::add_pixel_image) and show it with a sprite
+/// whose [`pixel_image`](crate::prelude::Sprite::pixel_image) is set to its label. Pixels are
+/// numbered from the top-left corner, and are drawn sharp (not blurred) when the sprite is scaled
+/// up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PixelImage {
+    width: u32,
+    height: u32,
+    // four bytes (red, green, blue, alpha) per pixel, row by row, in sRGB
+    data: Vec<u8>,
+    // whether the pixels need to be sent to Bevy again
+    changed: bool,
+}
+
+impl PixelImage {
+    /// Create an image of the given size, with every pixel fully transparent
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+            changed: true,
+        }
+    }
+
+    /// Create an image from a buffer of RGBA bytes -- four bytes (red, green, blue, alpha) for each
+    /// pixel, row by row, starting at the top-left corner. Returns
+    /// [`EngineError::PixelImageSize`] if the buffer isn't `width * height * 4` bytes long.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Result<Self, EngineError> {
+        let expected = width as usize * height as usize * 4;
+        if data.len() != expected {
+            return Err(EngineError::PixelImageSize {
+                expected,
+                actual: data.len(),
+            });
+        }
+        Ok(Self {
+            width,
+            height,
+            data,
+            changed: true,
+        })
+    }
+
+    /// The width of the image in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The size of the image in pixels
+    pub fn size(&self) -> UVec2 {
+        UVec2::new(self.width, self.height)
+    }
+
+    /// The RGBA bytes of the image, four per pixel, row by row
+    pub fn rgba(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The RGBA bytes of the image, four per pixel, row by row, for changing lots of pixels
+    /// quickly. The image is sent to Bevy again at the end of the frame.
+    pub fn rgba_mut(&mut self) -> &mut [u8] {
+        self.changed = true;
+        &mut self.data
+    }
+
+    /// The color of the pixel at column `x` and row `y`, or `None` if that is outside the image
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Color> {
+        let index = self.index(x, y)?;
+        let [r, g, b, a] = <[u8; 4]>::try_from(self.data.get(index..index + 4)?).ok()?;
+        Some(Color::srgba_u8(r, g, b, a))
+    }
+
+    /// Change the color of the pixel at column `x` and row `y`. Pixels outside the image are
+    /// ignored.
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: Color) {
+        if let Some(index) = self.index(x, y) {
+            self.data[index..index + 4].copy_from_slice(&color.to_srgba().to_u8_array());
+            self.changed = true;
+        }
+    }
+
+    /// Change the color of every pixel
+    pub fn fill(&mut self, color: Color) {
+        let rgba = color.to_srgba().to_u8_array();
+        for pixel in self.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&rgba);
+        }
+        self.changed = true;
+    }
+
+    /// Change the size of the image. Pixels that are still inside the image keep their colors, and
+    /// new pixels are fully transparent.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let mut resized = Self::new(width, height);
+        // Either image may have a width of zero, in which case there's nothing to copy
+        let row = width.min(self.width) as usize * 4;
+        for y in 0..height.min(self.height) as usize {
+            let from = y * self.width as usize * 4;
+            let to = y * width as usize * 4;
+            resized.data[to..to + row].copy_from_slice(&self.data[from..from + row]);
+        }
+        *self = resized;
+    }
+
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        (x < self.width && y < self.height)
+            .then(|| (y as usize * self.width as usize + x as usize) * 4)
+    }
+
+    /// Convert to a Bevy image
+    pub(crate) fn bevy_image(&self) -> Image {
+        let mut image = Image::new(
+            Extent3d {
+                width: self.width.max(1),
+                height: self.height.max(1),
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            if self.data.is_empty() {
+                vec![0; 4]
+            } else {
+                self.data.clone()
+            },
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.sampler = ImageSampler::nearest();
+        image
+    }
+}
+
+/// system - send new and changed pixel images to Bevy, and show them on the sprites that use them
+fn sync_pixel_images(
+    mut engine: ResMut<Engine>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut handles: Local<HashMap<String, Handle<Image>>>,
+    mut query: Query<(&Sprite, &mut BevySprite)>,
+) {
+    handles.retain(|label, _| engine.pixel_images.contains_key(label));
+    for (label, pixel_image) in engine.pixel_images.iter_mut() {
+        match handles.get(label) {
+            Some(handle) => {
+                if pixel_image.changed {
+                    // Can't fail, since the handle keeps the image alive
+                    let _ = images.insert(handle, pixel_image.bevy_image());
+                }
+            }
+            None => {
+                handles.insert(label.clone(), images.add(pixel_image.bevy_image()));
+            }
+        }
+        pixel_image.changed = false;
+    }
+    let ours: HashSet<AssetId<Image>> = handles.values().map(Handle::id).collect();
+    for (sprite, mut bevy_sprite) in query.iter_mut() {
+        // A playing animation decides which image is shown
+        if sprite
+            .animation
+            .as_ref()
+            .is_some_and(|name| engine.animations.contains_key(name))
+        {
+            continue;
+        }
+        let image = match &sprite.pixel_image {
+            Some(label) => handles.get(label).cloned().unwrap_or_default(),
+            // Put the sprite's own image back if it stopped using a pixel image
+            None if ours.contains(&bevy_sprite.image.id()) => {
+                if sprite.filepath.as_os_str().is_empty() {
+                    Handle::default()
+                } else {
+                    asset_server.load(sprite.filepath.clone())
+                }
+            }
+            None => continue,
+        };
+        if bevy_sprite.image != image {
+            bevy_sprite.image = image;
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Facilities for recording keyboard and mouse input to a file, and replaying it later. A
+//! recording captures everything that the input fields of the [`Engine`] contained each frame,
+//! along with that frame's `delta`, so replaying it makes your logic functions see exactly the same
+//! input (and time) that they saw while recording. This is handy for reproducing bugs that
+//! playtesters ran into, and for turning real play sessions into attract-mode demos.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! // Record the whole play session. The file is written when the game exits.
+//! game.record_input_to_file("playtest.ron");
+//! game.run(GameState);
+//! # }
+//! ```
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! // Play the session back. Live input is ignored until the recording runs out.
+//! let recording = InputRecording::read_from_file("playtest.ron").unwrap();
+//! game.replay_input(recording);
+//! game.run(GameState);
+//! # }
+//! ```
+//!
+//! For the replay to match the recording, your game needs to start out the same way and your
+//! logic needs to be deterministic -- if you use random numbers, store the seed in your game state
+//! and use the same one.
+
+use crate::{
+    error::EngineError,
+    mouse::{CursorMoved, MouseButtonInput, MouseMotion, MouseWheel},
+    prelude::{Engine, KeyboardInput, KeyboardState, MouseState},
+};
+use bevy::{app::AppExit, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub(crate) struct RecordingPlugin;
+
+impl Plugin for RecordingPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(Last, save_recording_on_exit);
+    }
+}
+
+/// All the input that the [`Engine`] provided to the logic functions during a single frame. When a
+/// frame is replayed, the `window` of each event is replaced with the current primary window (or
+/// [`Entity::PLACEHOLDER`] when running headless).
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RecordedFrame {
+    /// The value of [`Engine::delta`] for this frame
+    pub delta: Duration,
+    /// The value of [`Engine::time_since_startup`] for this frame
+    pub time_since_startup: Duration,
+    /// The value of [`Engine::keyboard_state`] for this frame
+    pub keyboard_state: KeyboardState,
+    /// The value of [`Engine::keyboard_events`] for this frame
+    pub keyboard_events: Vec<KeyboardInput>,
+    /// The value of [`Engine::mouse_state`] for this frame
+    pub mouse_state: MouseState,
+    /// The value of [`Engine::mouse_button_events`] for this frame
+    pub mouse_button_events: Vec<MouseButtonInput>,
+    /// The value of [`Engine::mouse_location_events`] for this frame
+    pub mouse_location_events: Vec<CursorMoved>,
+    /// The value of [`Engine::mouse_motion_events`] for this frame
+    pub mouse_motion_events: Vec<MouseMotion>,
+    /// The value of [`Engine::mouse_wheel_events`] for this frame
+    pub mouse_wheel_events: Vec<MouseWheel>,
+}
+
+impl RecordedFrame {
+    /// Capture the input the engine currently holds
+    fn capture(engine: &Engine) -> Self {
+        Self {
+            delta: engine.delta,
+            time_since_startup: engine.time_since_startup,
+            keyboard_state: engine.keyboard_state.clone(),
+            keyboard_events: engine.keyboard_events.clone(),
+            mouse_state: engine.mouse_state.clone(),
+            mouse_button_events: engine.mouse_button_events.clone(),
+            mouse_location_events: engine.mouse_location_events.clone(),
+            mouse_motion_events: engine.mouse_motion_events.clone(),
+            mouse_wheel_events: engine.mouse_wheel_events.clone(),
+        }
+    }
+
+    /// Overwrite the input the engine currently holds. Events that say which window they happened
+    /// in are moved over to `window`, since the recorded window is long gone.
+    fn apply(&self, engine: &mut Engine, window: Entity) {
+        engine.delta = self.delta;
+        engine.delta_f32 = self.delta.as_secs_f32();
+        engine.time_since_startup = self.time_since_startup;
+        engine.time_since_startup_f64 = self.time_since_startup.as_secs_f64();
+        engine.keyboard_state = self.keyboard_state.clone();
+        engine.keyboard_events = self.keyboard_events.clone();
+        engine.mouse_state = self.mouse_state.clone();
+        engine.mouse_button_events = self.mouse_button_events.clone();
+        engine.mouse_location_events = self.mouse_location_events.clone();
+        engine.mouse_motion_events = self.mouse_motion_events.clone();
+        engine.mouse_wheel_events = self.mouse_wheel_events.clone();
+        for event in engine.keyboard_events.iter_mut() {
+            event.window = window;
+        }
+        for event in engine.mouse_button_events.iter_mut() {
+            event.window = window;
+        }
+        for event in engine.mouse_location_events.iter_mut() {
+            event.window = window;
+        }
+        for event in engine.mouse_wheel_events.iter_mut() {
+            event.window = window;
+        }
+    }
+}
+
+/// A recording of input, one [`RecordedFrame`] per frame. Get one from
+/// [`Engine::stop_recording`] or [`InputRecording::read_from_file`], and play it back with
+/// [`Engine::replay_input`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct InputRecording {
+    /// The recorded frames, in the order they happened
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl InputRecording {
+    /// Attempt to read a recording from a RON file.
+    pub fn read_from_file<P: AsRef<Path>>(filepath: P) -> Result<Self, EngineError> {
+        let filepath = filepath.as_ref();
+        let contents = std::fs::read_to_string(filepath).map_err(|error| EngineError::Io {
+            filepath: filepath.to_path_buf(),
+            error,
+        })?;
+        ron::from_str::<InputRecording>(&contents).map_err(|error| EngineError::Parse {
+            filepath: filepath.to_path_buf(),
+            error: Box::new(error),
+        })
+    }
+
+    /// Attempt to write the recording to a RON file.
+    pub fn write_to_file<P: AsRef<Path>>(&self, filepath: P) -> Result<(), EngineError> {
+        let filepath = filepath.as_ref();
+        let recording_ron =
+            ron::ser::to_string_pretty(self, Default::default()).map_err(EngineError::Serialize)?;
+        File::create(filepath)
+            .and_then(|mut fh| fh.write_all(recording_ron.as_bytes()))
+            .map_err(|error| EngineError::Io {
+                filepath: filepath.to_path_buf(),
+                error,
+            })
+    }
+}
+
+/// Whether input is currently being recorded, replayed, or neither
+#[derive(Debug, Default)]
+pub(crate) enum InputRecorder {
+    #[default]
+    Idle,
+    Recording {
+        recording: InputRecording,
+        // where to write the recording when it stops, if anywhere
+        filepath: Option<PathBuf>,
+    },
+    Replaying {
+        recording: InputRecording,
+        next_frame: usize,
+    },
+}
+
+impl InputRecorder {
+    /// Called each frame after the input has been copied into the engine, and before the logic
+    /// functions run. Records the input, or replaces it with the recorded input. `window` is the
+    /// primary window, or [`Entity::PLACEHOLDER`] if there isn't one.
+    pub(crate) fn update(engine: &mut Engine, window: Entity) {
+        if matches!(engine.input_recorder, InputRecorder::Recording { .. }) {
+            let frame = RecordedFrame::capture(engine);
+            if let InputRecorder::Recording { recording, .. } = &mut engine.input_recorder {
+                recording.frames.push(frame);
+            }
+        } else if let InputRecorder::Replaying {
+            recording,
+            next_frame,
+        } = &mut engine.input_recorder
+        {
+            let Some(frame) = recording.frames.get(*next_frame).cloned() else {
+                // The recording ran out, so go back to live input
+                engine.input_recorder = InputRecorder::Idle;
+                return;
+            };
+            *next_frame += 1;
+            frame.apply(engine, window);
+        }
+    }
+}
+
+/// system - write out the recording (if it should be written to a file) when the game exits
+fn save_recording_on_exit(mut engine: ResMut<Engine>, mut app_exit_events: MessageReader<AppExit>) {
+    if app_exit_events.read().next().is_none() {
+        return;
+    }
+    if let InputRecorder::Recording {
+        filepath: Some(_), ..
+    } = engine.input_recorder
+    {
+        engine.stop_recording();
+    }
+}
@@ -0,0 +1,176 @@
+//! Facilities for dividing a game into scenes, such as a menu, the gameplay itself, and a game over
+//! screen. Each [`Scene`] has its own logic functions, which only run while the scene is active,
+//! plus optional functions to run when the scene is entered and exited.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn menu_setup(engine: &mut Engine, game_state: &mut GameState) {}
+//! # fn menu_logic(engine: &mut Engine, game_state: &mut GameState) {}
+//! # fn play_setup(engine: &mut Engine, game_state: &mut GameState) {}
+//! # fn play_logic(engine: &mut Engine, game_state: &mut GameState) {}
+//! # fn main() {
+//! let mut game = Game::new();
+//! game.add_scene("menu", Scene::new().on_enter(menu_setup).with_logic(menu_logic));
+//! game.add_scene("play", Scene::new().on_enter(play_setup).with_logic(play_logic));
+//! game.switch_scene("menu");
+//! game.run(GameState);
+//! # }
+//! ```
+//!
+//! When a scene is exited, all the sprites and texts that were added while it was active are
+//! removed, unless their `persistent` field is set to `true`. To move on to another scene, call
+//! [`Engine::switch_scene`](crate::prelude::Engine::switch_scene) from a logic function.
+
+use crate::{game::BoxedLogic, prelude::Engine};
+use bevy::{platform::collections::HashMap, prelude::*};
+
+/// A [`Scene`] is one part of a game, with its own logic functions. Create one with
+/// [`Scene::new`], set it up with the builder methods, and then add it to the game with
+/// [`Game::add_scene`](crate::prelude::Game::add_scene).
+pub struct Scene<S: Resource + Send + Sync + 'static> {
+    pub(crate) on_enter: Option<BoxedLogic<S>>,
+    pub(crate) on_exit: Option<BoxedLogic<S>>,
+    pub(crate) logic_functions: Vec<SceneLogic<S>>,
+}
+
+/// A logic function belonging to a scene, waiting to be added to the game
+pub(crate) struct SceneLogic<S: Resource + Send + Sync + 'static> {
+    pub(crate) hz: Option<f32>,
+    pub(crate) priority: i32,
+    pub(crate) func: BoxedLogic<S>,
+}
+
+impl<S: Resource + Send + Sync + 'static> Default for Scene<S> {
+    fn default() -> Self {
+        Self {
+            on_enter: None,
+            on_exit: None,
+            logic_functions: Vec::new(),
+        }
+    }
+}
+
+impl<S: Resource + Send + Sync + 'static> Scene<S> {
+    /// Create a new, empty [`Scene`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// A function (or closure) to run once when the scene is entered. This is a great place to add
+    /// the sprites and texts for the scene.
+    pub fn on_enter<F>(mut self, func: F) -> Self
+    where
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        self.on_enter = Some(Box::new(func));
+        self
+    }
+
+    /// A function (or closure) to run once when the scene is exited, just before its sprites and
+    /// texts are removed.
+    pub fn on_exit<F>(mut self, func: F) -> Self
+    where
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        self.on_exit = Some(Box::new(func));
+        self
+    }
+
+    /// A logic function (or closure) which runs each frame while the scene is active. See
+    /// [`Game::add_logic`](crate::prelude::Game::add_logic).
+    pub fn with_logic<F>(self, func: F) -> Self
+    where
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        self.with_logic_priority(func, 0)
+    }
+
+    /// Same as [`with_logic`](Scene::with_logic), but with a priority. See
+    /// [`Game::add_logic_with_priority`](crate::prelude::Game::add_logic_with_priority).
+    pub fn with_logic_priority<F>(mut self, func: F, priority: i32) -> Self
+    where
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        self.logic_functions.push(SceneLogic {
+            hz: None,
+            priority,
+            func: Box::new(func),
+        });
+        self
+    }
+
+    /// A fixed logic function (or closure) which runs `hz` times per second while the scene is
+    /// active. See [`Game::add_fixed_logic`](crate::prelude::Game::add_fixed_logic).
+    pub fn with_fixed_logic<F>(mut self, hz: f32, func: F) -> Self
+    where
+        F: FnMut(&mut Engine, &mut S) + Send + Sync + 'static,
+    {
+        self.logic_functions.push(SceneLogic {
+            hz: Some(hz),
+            priority: 0,
+            func: Box::new(func),
+        });
+        self
+    }
+}
+
+/// All the scenes, by name. The logic functions have been moved into the game's logic functions by
+/// the time a scene ends up in here, so only the enter and exit functions are left.
+#[derive(Resource)]
+pub(crate) struct Scenes<S: Resource + Send + Sync + 'static>(pub(crate) HashMap<String, Scene<S>>);
+
+impl<S: Resource + Send + Sync + 'static> Default for Scenes<S> {
+    fn default() -> Self {
+        Self(HashMap::default())
+    }
+}
+
+impl<S: Resource + Send + Sync + 'static> Scenes<S> {
+    /// If a scene switch was requested, exit the current scene and enter the new one
+    pub(crate) fn switch_scene(&mut self, engine: &mut Engine, game_state: &mut S) {
+        let Some(next_scene) = engine.requested_scene.take() else {
+            return;
+        };
+        if !self.0.contains_key(&next_scene) {
+            warn!("Ignoring request to switch to unknown scene {next_scene:?}");
+            return;
+        }
+        if let Some(current_scene) = engine.current_scene.clone() {
+            if let Some(on_exit) = self
+                .0
+                .get_mut(&current_scene)
+                .and_then(|scene| scene.on_exit.as_mut())
+            {
+                on_exit(engine, game_state);
+            }
+            let current_scene = Some(current_scene);
+            engine
+                .sprites
+                .retain(|_, sprite| sprite.persistent || sprite.scene != current_scene);
+            engine
+                .texts
+                .retain(|_, text| text.persistent || text.scene != current_scene);
+            engine
+                .tilemaps
+                .retain(|_, tilemap| tilemap.persistent || tilemap.scene != current_scene);
+            engine
+                .shapes
+                .retain(|_, shape| shape.persistent || shape.scene != current_scene);
+            engine
+                .emitters
+                .retain(|_, emitter| emitter.persistent || emitter.scene != current_scene);
+        }
+        engine.current_scene = Some(next_scene.clone());
+        if let Some(on_enter) = self
+            .0
+            .get_mut(&next_scene)
+            .and_then(|scene| scene.on_enter.as_mut())
+        {
+            on_enter(engine, game_state);
+        }
+    }
+}
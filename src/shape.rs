@@ -0,0 +1,280 @@
+//! Facilities for drawing rectangles, circles, polygons and lines without any image files. A
+//! [`Shape`] is placed, rotated, and scaled just like a sprite, and can collide with sprites,
+//! tilemaps, and other shapes.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! let paddle = game.add_shape("paddle", ShapeKind::Rectangle(Vec2::new(120.0, 20.0)));
+//! paddle.translation.y = -300.0;
+//! paddle.fill = Some(Color::srgb(0.2, 0.6, 1.0));
+//! paddle.collision = true;
+//! let ball = game.add_shape("ball", ShapeKind::Circle(10.0));
+//! ball.stroke = Some(Color::BLACK);
+//! ball.stroke_width = 2.0;
+//! ball.collision = true;
+//! game.run(GameState);
+//! # }
+//! ```
+
+use crate::{
+    physics::{Collider, convex_collider},
+    prelude::Engine,
+    sprite::Sprite,
+};
+use bevy::{platform::collections::HashSet, prelude::*};
+use bevy_prototype_lyon::prelude::{
+    Shape as LyonShape, ShapeBuilder, ShapeBuilderBase, Stroke, shapes,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub(crate) struct ShapesPlugin;
+
+impl Plugin for ShapesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, sync_shapes);
+    }
+}
+
+/// The outline of a [`Shape`]. Points are relative to the shape's `translation`, before rotation
+/// and scale are applied.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum ShapeKind {
+    /// A rectangle of this size (width, height), centered on the shape's `translation`
+    Rectangle(Vec2),
+    /// A circle with this radius, centered on the shape's `translation`
+    Circle(f32),
+    /// A closed polygon through these points
+    Polygon(Vec<Vec2>),
+    /// An open line through these points. Polylines are never filled, and never collide.
+    Polyline(Vec<Vec2>),
+}
+
+impl ShapeKind {
+    /// Whether the shape encloses an area (everything except a polyline)
+    pub fn is_closed(&self) -> bool {
+        !matches!(self, ShapeKind::Polyline(_))
+    }
+}
+
+/// A [`Shape`] is a rectangle, circle, polygon or line that is drawn without an image file. Create
+/// one with [`Engine::add_shape`](crate::prelude::Engine::add_shape).
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Shape {
+    /// READONLY: A label to identify the shape. This must be unique among shapes, or the shape will
+    /// replace the existing shape with the same label. Collision events use this label, so it
+    /// shouldn't be the same as the label of a sprite or tilemap.
+    pub label: String,
+    /// SYNCED: What to draw
+    pub kind: ShapeKind,
+    /// SYNCED: Where you are in 2D game space. Positive x is right. Positive y is up. (0.0, 0.0) is
+    /// the center of the screen.
+    pub translation: Vec2,
+    /// SYNCED: Depth of the shape. 0.0 (back) to 999.0 (front)
+    pub layer: f32,
+    /// SYNCED: Direction you face in radians. See constants UP, DOWN, LEFT, RIGHT
+    pub rotation: f32,
+    /// SYNCED: 1.0 is the normal 100%. The stroke is scaled too.
+    pub scale: f32,
+    /// SYNCED: The color to fill the shape with, or `None` to leave it empty. Defaults to
+    /// [`Color::WHITE`], except for polylines, which can't be filled.
+    pub fill: Option<Color>,
+    /// SYNCED: The color of the outline, or `None` for no outline. Defaults to `None`, except for
+    /// polylines, whose line is [`Color::WHITE`].
+    pub stroke: Option<Color>,
+    /// SYNCED: The width of the outline. Defaults to `1.0`.
+    pub stroke_width: f32,
+    /// SYNCED: Whether or not the shape is drawn. Defaults to `true`. Hidden shapes don't collide.
+    pub visible: bool,
+    /// SYNCED: Whether or not to calculate collisions, using the collider from
+    /// [`Shape::collider`]. Defaults to `false`.
+    pub collision: bool,
+    /// READONLY: The scene that was active when this shape was added with
+    /// [`Engine::add_shape`](crate::prelude::Engine::add_shape), if any. When that scene is
+    /// exited, this shape is removed (unless it is `persistent`).
+    #[serde(default)]
+    pub scene: Option<String>,
+    /// SYNCED: If `true`, this shape is not removed when the scene it was added in is exited.
+    #[serde(default)]
+    pub persistent: bool,
+}
+
+impl Shape {
+    /// Create a shape. Usually you want [`Engine::add_shape`](crate::prelude::Engine::add_shape)
+    /// instead.
+    pub fn new<T: Into<String>>(label: T, kind: ShapeKind) -> Self {
+        let closed = kind.is_closed();
+        Self {
+            label: label.into(),
+            kind,
+            translation: Vec2::ZERO,
+            layer: 0.0,
+            rotation: 0.0,
+            scale: 1.0,
+            fill: closed.then_some(Color::WHITE),
+            stroke: (!closed).then_some(Color::WHITE),
+            stroke_width: 1.0,
+            visible: true,
+            collision: false,
+            scene: None,
+            persistent: false,
+        }
+    }
+
+    /// A [`Collider`] that matches the outline of the shape. Circles get a 16-sided polygon, and
+    /// polylines (and polygons with fewer than three points) get [`Collider::NoCollider`].
+    /// Collision detection only works with convex polygons, so a concave polygon gets its convex
+    /// hull instead (and a warning is logged when the shape collides). This is the collider used
+    /// when `collision` is `true`, but you can also give it to a sprite.
+    pub fn collider(&self) -> Collider {
+        match &self.kind {
+            ShapeKind::Rectangle(size) => {
+                let half = *size * 0.5;
+                Collider::rect(Vec2::new(-half.x, half.y), Vec2::new(half.x, -half.y))
+            }
+            ShapeKind::Circle(radius) => Collider::circle(*radius),
+            ShapeKind::Polygon(points) => convex_collider(points.clone(), None),
+            ShapeKind::Polyline(_) => Collider::NoCollider,
+        }
+    }
+
+    /// Log a warning if the shape is a concave polygon, whose collider is its convex hull
+    fn warn_if_concave(&self) {
+        if let ShapeKind::Polygon(points) = &self.kind {
+            convex_collider(points.clone(), Some(&format!("Shape {:?}", self.label)));
+        }
+    }
+
+    /// A sprite with the shape's placement and collider, for collision detection
+    pub(crate) fn collision_sprite(&self) -> Sprite {
+        let mut sprite = Sprite::with_collider(self.label.clone(), PathBuf::new(), self.collider());
+        sprite.translation = self.translation;
+        sprite.rotation = self.rotation;
+        sprite.scale = self.scale;
+        sprite.collision = true;
+        sprite
+    }
+
+    /// Whether collisions should be calculated for this shape right now
+    pub(crate) fn collides(&self) -> bool {
+        self.collision && self.visible
+    }
+
+    /// Do the math to convert to Bevy's Transform
+    fn bevy_transform(&self) -> Transform {
+        let mut transform = Transform::from_translation(self.translation.extend(self.layer));
+        transform.rotation = Quat::from_axis_angle(Vec3::Z, self.rotation);
+        transform.scale = Vec3::splat(self.scale);
+        transform
+    }
+
+    /// Convert `visible` to Bevy's Visibility
+    fn bevy_visibility(&self) -> Visibility {
+        if self.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    }
+
+    /// Tessellate the shape with bevy_prototype_lyon
+    fn lyon_shape(&self) -> LyonShape {
+        let builder = match &self.kind {
+            ShapeKind::Rectangle(size) => {
+                let half = *size * 0.5;
+                ShapeBuilder::with(&shapes::Polygon {
+                    points: vec![
+                        Vec2::new(-half.x, half.y),
+                        half,
+                        Vec2::new(half.x, -half.y),
+                        -half,
+                    ],
+                    closed: true,
+                })
+            }
+            ShapeKind::Circle(radius) => ShapeBuilder::with(&shapes::Circle {
+                radius: *radius,
+                center: Vec2::ZERO,
+            }),
+            ShapeKind::Polygon(points) | ShapeKind::Polyline(points) => {
+                ShapeBuilder::with(&shapes::Polygon {
+                    points: points.clone(),
+                    closed: self.kind.is_closed(),
+                })
+            }
+        };
+        let fill = self.fill.filter(|_| self.kind.is_closed());
+        let stroke = self
+            .stroke
+            .map(|color| Stroke::new(color, self.stroke_width));
+        match (fill, stroke) {
+            (Some(fill), Some(stroke)) => builder.fill(fill).stroke(stroke).build(),
+            (Some(fill), None) => builder.fill(fill).build(),
+            (None, Some(stroke)) => builder.stroke(stroke).build(),
+            (None, None) => builder.fill(Color::NONE).build(),
+        }
+    }
+}
+
+/// The shape that an entity was last synced from
+#[derive(Component)]
+struct SyncedShape(Shape);
+
+/// system - spawn, update, and despawn the entities for the shapes in the engine. Shapes are only
+/// tessellated again when their kind or colors change.
+fn sync_shapes(
+    mut commands: Commands,
+    engine: Res<Engine>,
+    mut query: Query<(
+        Entity,
+        &mut SyncedShape,
+        &mut LyonShape,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+) {
+    let mut existing = HashSet::new();
+    for (entity, mut synced, mut lyon_shape, mut transform, mut visibility) in query.iter_mut() {
+        let Some(shape) = engine.shapes.get(&synced.0.label) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        existing.insert(shape.label.clone());
+        if synced.0 == *shape {
+            continue;
+        }
+        if shape.collision && (synced.0.kind != shape.kind || !synced.0.collision) {
+            shape.warn_if_concave();
+        }
+        if synced.0.kind != shape.kind
+            || synced.0.fill != shape.fill
+            || synced.0.stroke != shape.stroke
+            || synced.0.stroke_width != shape.stroke_width
+        {
+            *lyon_shape = shape.lyon_shape();
+        }
+        *transform = shape.bevy_transform();
+        *visibility = shape.bevy_visibility();
+        synced.0 = shape.clone();
+    }
+    for shape in engine.shapes.values() {
+        if existing.contains(&shape.label) {
+            continue;
+        }
+        if shape.collision {
+            shape.warn_if_concave();
+        }
+        commands.spawn((
+            SyncedShape(shape.clone()),
+            shape.lyon_shape(),
+            shape.bevy_transform(),
+            shape.bevy_visibility(),
+        ));
+    }
+}
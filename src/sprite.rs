@@ -1,16 +1,31 @@
 /// Sprites are the images that make up a game
-use bevy::prelude::{Component, Quat, Transform, Vec2, Vec3};
+use bevy::{
+    color::{Alpha, Color},
+    platform::collections::{HashMap, HashSet},
+    prelude::{Component, Quat, Rect, Transform, Vec2, Vec3, Visibility},
+    sprite::Anchor as BevyAnchor,
+};
+use serde::{Deserialize, Serialize};
 
-use crate::physics::Collider;
+use crate::{animation::AnimationState, error::EngineError, path::SpritePath, physics::Collider};
 
 /// A [`Sprite`] is the basic abstraction for something that can be seen and interacted with.
 /// Players, obstacles, etc. are all sprites.
-#[derive(Clone, Component, Debug, PartialEq)]
+///
+/// Sprites can be serialized (for example, with [ron](https://docs.rs/ron)) to save levels to a
+/// file. INFO fields are not saved.
+#[derive(Clone, Component, Debug, Deserialize, Serialize, PartialEq)]
 pub struct Sprite {
-    /// READONLY: A way to identify a sprite. This must be unique, or else the game will crash.
+    /// READONLY: A way to identify a sprite. This must be unique, or else the sprite will replace
+    /// the existing sprite with the same label.
     pub label: String,
     /// READONLY: File used for this sprite's image
     pub filepath: PathBuf,
+    /// SYNCED: The label of a [`PixelImage`](crate::prelude::PixelImage) in
+    /// [`Engine::pixel_images`](crate::prelude::Engine::pixel_images) to show instead of the image
+    /// at `filepath`, if any. See [`Engine::add_pixel_sprite`](crate::prelude::Engine::add_pixel_sprite).
+    #[serde(default)]
+    pub pixel_image: Option<String>,
     /// READONLY: File used for this sprite's collider. Note that this file will not exist if the
     /// sprite does not have a collider, but if you set the `collider` field to a collider and then
     /// call the `write_collider` method, the file will be written for you!
@@ -20,36 +35,150 @@ pub struct Sprite {
     pub translation: Vec2,
     /// SYNCED: Depth of the sprite. 0.0 (back) to 999.0 (front)
     pub layer: f32,
+    /// SYNCED: The point on the sprite's image that `translation` refers to, and that the sprite
+    /// rotates, scales, and flips around. Defaults to [`Anchor::Center`].
+    #[serde(default)]
+    pub anchor: Anchor,
     /// SYNCED: Direction you face in radians. See constants UP, DOWN, LEFT, RIGHT
     pub rotation: f32,
+    /// SYNCED: How fast the sprite moves, in pixels per second. Each frame, after the logic
+    /// functions run (and before collisions are detected), the engine moves `translation` along
+    /// by `velocity`. If the game has a fixed logic function (see
+    /// [`Game::add_fixed_logic`](crate::game::Game::add_fixed_logic)), that happens in each of its
+    /// fixed steps instead. Defaults to `Vec2::ZERO`.
+    #[serde(default)]
+    pub velocity: Vec2,
+    /// SYNCED: How fast `velocity` changes, in pixels per second per second. Try
+    /// `Vec2::new(0.0, -500.0)` for gravity. Defaults to `Vec2::ZERO`.
+    #[serde(default)]
+    pub acceleration: Vec2,
+    /// SYNCED: How fast the sprite spins, in radians per second. Positive is counterclockwise.
+    /// Defaults to `0.0`.
+    #[serde(default)]
+    pub angular_velocity: f32,
+    /// SYNCED: How quickly the sprite slows down by itself, like friction or air resistance. At
+    /// `1.0` a sprite loses about 63% of its speed each second, and at `0.0` (the default) it
+    /// never slows down.
+    #[serde(default)]
+    pub drag: f32,
+    /// SYNCED: The fastest the sprite is allowed to move, in pixels per second. `velocity` is
+    /// clamped to this each frame. Defaults to `None`, for no limit.
+    #[serde(default)]
+    pub max_speed: Option<f32>,
+    /// SYNCED: A route for the sprite to follow on its own, if any. Each frame, before the logic
+    /// functions run, the engine moves `translation` along the path. See
+    /// [`Sprite::follow_path`]. Defaults to `None`.
+    #[serde(default)]
+    pub path: Option<SpritePath>,
     /// SYNCED: 1.0 is the normal 100%
     pub scale: f32,
+    /// SYNCED: If set, the sprite is scaled separately along its x and y axes by these amounts
+    /// instead of by `scale`. Handy for stretching a barrier into a long wall.
+    #[serde(default)]
+    pub scale_xy: Option<Vec2>,
+    /// SYNCED: If `true`, the sprite (and its collider) is mirrored horizontally
+    #[serde(default)]
+    pub flip_x: bool,
+    /// SYNCED: If `true`, the sprite (and its collider) is mirrored vertically
+    #[serde(default)]
+    pub flip_y: bool,
+    /// SYNCED: The label of the sprite this sprite is attached to, if any. When set, `translation`,
+    /// `rotation` and `scale` are relative to the parent sprite, so this sprite moves, turns and
+    /// scales along with it. See [`Engine::sprite_world_transform`](crate::prelude::Engine::sprite_world_transform).
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// SYNCED: Whether or not the sprite is drawn. Defaults to `true`. Hiding a sprite keeps it
+    /// (and its collisions) around, unlike removing it from
+    /// [`Engine::sprites`](crate::prelude::Engine::sprites).
+    #[serde(default = "default_true")]
+    pub visible: bool,
     /// Whether or not to calculate collisions
     pub collision: bool,
+    /// Whether or not to keep calculating collisions while the sprite is hidden (see `visible`).
+    /// Defaults to `false`, so hidden sprites don't collide with anything.
+    #[serde(default)]
+    pub collide_while_hidden: bool,
     /// The actual collider for this sprite
     pub collider: Collider,
     /// If set to `true`, then the collider shown for this sprite will be regenerated (see also
     /// [`Engine.show_colliders`](crate::prelude::Engine)). Normally you shouldn't touch this, but
     /// if you manually replace a `Sprite`'s [`Collider`] in a game logic function, then you need to
     /// set this to true.
+    #[serde(skip, default = "default_true")]
     pub collider_dirty: bool,
+    /// SYNCED: The color to tint the sprite's image with. The default, [`Color::WHITE`], leaves the
+    /// image unchanged. Try `Color::srgb(1.0, 0.0, 0.0)` to flash a sprite red.
+    #[serde(default = "default_color")]
+    pub color: Color,
+    /// SYNCED: How opaque the sprite is, from `0.0` (invisible) to `1.0` (fully opaque, the
+    /// default). This is multiplied with the alpha of `color`, so it's handy for fading a sprite
+    /// in or out without touching its tint.
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+    /// READONLY: The scene that was active when this sprite was added with
+    /// [`Engine::add_sprite`](crate::prelude::Engine::add_sprite), if any. When that scene is
+    /// exited, this sprite is removed (unless it is `persistent`).
+    pub scene: Option<String>,
+    /// SYNCED: If `true`, this sprite is not removed when the scene it was added in is exited.
+    #[serde(default)]
+    pub persistent: bool,
+    /// INFO: The size of this sprite's image in pixels, before `scale` is applied. This is `None`
+    /// until the image has finished loading, which usually takes a few frames after the sprite is
+    /// added (see [`Engine::all_sprites_loaded`](crate::prelude::Engine::all_sprites_loaded)).
+    #[serde(skip)]
+    pub image_size: Option<Vec2>,
+    /// SYNCED: The name of the animation this sprite is playing, if any. Animations are added with
+    /// [`Engine::add_animation`](crate::prelude::Engine::add_animation). Use
+    /// [`Sprite::play_animation`] to start an animation from its first frame.
+    #[serde(default)]
+    pub animation: Option<String>,
+    /// SYNCED: The current frame of the animation, starting at `0`
+    #[serde(default)]
+    pub animation_frame: usize,
+    /// SYNCED: If `true`, the animation stays on the current frame
+    #[serde(default)]
+    pub animation_paused: bool,
+    /// Custom properties for your game logic to use, such as the ones set on objects in the Tiled
+    /// editor (see [`TiledMap`](crate::prelude::TiledMap)). Rusty Engine doesn't use them itself.
+    #[serde(default)]
+    pub properties: BTreeMap<String, String>,
+    /// SYNCED: Tags that say what kind of thing this sprite is, such as `"enemy"` or `"bullet"`.
+    /// Look up sprites by tag with
+    /// [`Engine::sprites_with_tag`](crate::prelude::Engine::sprites_with_tag), and find out which
+    /// side of a collision has a tag with [`CollisionEvent::tagged`](crate::prelude::CollisionEvent::tagged).
+    /// Tags added directly to this set show up in `Engine`'s tag lookups on the next frame (or
+    /// right away for a sprite that was just added) -- use
+    /// [`Engine::tag_sprite`](crate::prelude::Engine::tag_sprite) to tag an existing sprite
+    /// immediately.
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    // animation bookkeeping
+    #[serde(skip)]
+    pub(crate) animation_state: AnimationState,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_color() -> Color {
+    Color::WHITE
+}
+
+fn default_opacity() -> f32 {
+    1.0
 }
 
 /// Reads the collider file and creates the collider
-fn read_collider_from_file(filepath: &Path) -> Collider {
-    match std::fs::read_to_string(filepath) {
-        Ok(contents) => match ron::from_str::<Collider>(&contents) {
-            Ok(collider) => collider,
-            Err(e) => {
-                eprintln!("failed deserializing collider from file: {}", e);
-                Collider::NoCollider
-            }
-        },
-        Err(e) => {
-            eprintln!("failed to open collider file: {}", e);
-            Collider::NoCollider
-        }
-    }
+fn read_collider_from_file(filepath: &Path) -> Result<Collider, EngineError> {
+    let contents = std::fs::read_to_string(filepath).map_err(|error| EngineError::Io {
+        filepath: filepath.to_path_buf(),
+        error,
+    })?;
+    ron::from_str::<Collider>(&contents).map_err(|error| EngineError::Parse {
+        filepath: filepath.to_path_buf(),
+        error: Box::new(error),
+    })
 }
 
 impl Sprite {
@@ -61,32 +190,163 @@ impl Sprite {
     /// create a collider file you can either run the `collider` example, or
     /// programmatically create a [`Collider`], set the sprite's `.collider` field to it, and call
     /// the sprite's `.write_collider()` method.  All presets have collider files already.
+    ///
+    /// If the collider file exists but can't be read, a message is printed and the sprite gets no
+    /// collider. Use [`Sprite::try_new`] if you would rather handle that yourself.
     pub fn new<S: Into<String>, P: Into<PathBuf>>(label: S, file_or_preset: P) -> Self {
         let label = label.into();
+        let filepath = file_or_preset.into();
+        match Self::try_new(label.clone(), filepath.clone()) {
+            Ok(sprite) => {
+                let actual_collider_filepath =
+                    PathBuf::from("assets").join(&sprite.collider_filepath);
+                if !actual_collider_filepath.exists() {
+                    eprintln!(
+                        "warning: could not find collider file {} -- consider creating one with the `collider` example.",
+                        actual_collider_filepath.to_string_lossy()
+                    );
+                }
+                sprite
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                Self::with_collider(label, filepath, Collider::NoCollider)
+            }
+        }
+    }
+
+    /// Same as [`Sprite::new`], except that if the collider file exists but can't be read or
+    /// parsed, an [`EngineError`] is returned. A missing collider file is not an error -- the
+    /// sprite simply has no collider.
+    pub fn try_new<S: Into<String>, P: Into<PathBuf>>(
+        label: S,
+        file_or_preset: P,
+    ) -> Result<Self, EngineError> {
         let filepath = file_or_preset.into();
         let mut collider_filepath = filepath.clone();
         collider_filepath.set_extension("collider");
         let actual_collider_filepath = PathBuf::from("assets").join(&collider_filepath);
         let collider = if actual_collider_filepath.exists() {
-            read_collider_from_file(actual_collider_filepath.as_path())
+            read_collider_from_file(actual_collider_filepath.as_path())?
         } else {
-            eprintln!(
-                "warning: could not find collider file {} -- consider creating one with the `collider` example.",
-                actual_collider_filepath.to_string_lossy()
-            );
             Collider::NoCollider
         };
+        Ok(Self::with_collider(label.into(), filepath, collider))
+    }
+
+    pub(crate) fn with_collider(label: String, filepath: PathBuf, collider: Collider) -> Self {
+        let mut collider_filepath = filepath.clone();
+        collider_filepath.set_extension("collider");
         Self {
             label,
             filepath,
+            pixel_image: None,
             collider_filepath,
             translation: Vec2::default(),
             layer: f32::default(),
             rotation: f32::default(),
+            velocity: Vec2::ZERO,
+            acceleration: Vec2::ZERO,
+            angular_velocity: 0.0,
+            drag: 0.0,
+            max_speed: None,
+            path: None,
             scale: 1.0,
+            scale_xy: None,
+            anchor: Anchor::Center,
+            flip_x: false,
+            flip_y: false,
+            parent: None,
+            visible: true,
             collision: false,
+            collide_while_hidden: false,
             collider,
             collider_dirty: true,
+            color: Color::WHITE,
+            opacity: 1.0,
+            scene: None,
+            persistent: false,
+            image_size: None,
+            animation: None,
+            animation_frame: 0,
+            animation_paused: false,
+            properties: BTreeMap::new(),
+            tags: BTreeSet::new(),
+            animation_state: AnimationState::default(),
+        }
+    }
+
+    /// Combine `color` and `opacity` into the color for Bevy's sprite
+    #[doc(hidden)]
+    pub fn bevy_color(&self) -> Color {
+        self.color
+            .with_alpha(self.color.alpha() * self.opacity.clamp(0.0, 1.0))
+    }
+
+    /// Move the sprite along by `delta` seconds of `velocity`, `acceleration`, `angular_velocity`,
+    /// and `drag`
+    pub(crate) fn apply_motion(&mut self, delta: f32) {
+        self.velocity += self.acceleration * delta;
+        if self.drag > 0.0 {
+            self.velocity *= (-self.drag * delta).exp();
+        }
+        if let Some(max_speed) = self.max_speed {
+            self.velocity = self.velocity.clamp_length_max(max_speed.max(0.0));
+        }
+        self.translation += self.velocity * delta;
+        self.rotation += self.angular_velocity * delta;
+    }
+
+    /// Whether the sprite has the tag (see [`Sprite::tags`])
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Whether collisions should be calculated for this sprite right now
+    pub(crate) fn collides(&self) -> bool {
+        self.collision && (self.visible || self.collide_while_hidden)
+    }
+
+    /// Convert `visible` to Bevy's Visibility
+    #[doc(hidden)]
+    pub fn bevy_visibility(&self) -> Visibility {
+        if self.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    }
+
+    /// Convert `anchor` to Bevy's Anchor
+    #[doc(hidden)]
+    pub fn bevy_anchor(&self) -> BevyAnchor {
+        BevyAnchor(self.anchor.as_vec())
+    }
+
+    /// The offset in pixels from the center of the sprite's image to its
+    /// [`anchor`](Sprite::anchor) point, before scaling. Colliders are relative to the center of
+    /// the image, so this is how far they are shifted.
+    ///
+    /// Until the image has loaded (see [`Sprite::image_size`]), and in headless games, where
+    /// images are never loaded, the size of the image is estimated from the collider: the
+    /// smallest rectangle centered on the image that holds the whole collider. Preset colliders
+    /// reach the edges of their images, so the offset doesn't change when the image loads. With a
+    /// custom collider that doesn't, the offset (and the collider) shifts once the image loads.
+    /// Without an image or a collider, this is [`Vec2::ZERO`].
+    pub fn anchor_offset(&self) -> Vec2 {
+        let size = self
+            .image_size
+            .unwrap_or_else(|| self.collider_extents() * 2.0);
+        self.anchor.as_vec() * size
+    }
+
+    /// How far the collider reaches from the center of the image along each axis
+    fn collider_extents(&self) -> Vec2 {
+        match &self.collider {
+            Collider::NoCollider => Vec2::ZERO,
+            Collider::Poly(points) => points
+                .iter()
+                .fold(Vec2::ZERO, |extents, point| extents.max(point.abs())),
         }
     }
 
@@ -95,41 +355,112 @@ impl Sprite {
     pub fn bevy_transform(&self) -> Transform {
         let mut transform = Transform::from_translation(self.translation.extend(self.layer));
         transform.rotation = Quat::from_axis_angle(Vec3::Z, self.rotation);
-        transform.scale = Vec3::splat(self.scale);
+        transform.scale = self.flipped_scale().extend(1.0);
         transform
     }
 
-    /// Attempt to take the current collider and write it to collider_filepath. If there isn't a
-    /// collider, or writing fails, then `false` is returned. Otherwise `true` is returned.
-    pub fn write_collider(&self) -> bool {
-        if self.collider == Collider::NoCollider {
-            return false;
+    /// The sprite's scale along its x and y axes: `scale_xy` if it is set, otherwise `scale` for
+    /// both axes.
+    pub fn scale_2d(&self) -> Vec2 {
+        self.scale_xy.unwrap_or(Vec2::splat(self.scale))
+    }
+
+    /// Same as [`scale_2d`](Sprite::scale_2d), but negative along the axes that are flipped
+    #[doc(hidden)]
+    pub fn flipped_scale(&self) -> Vec2 {
+        let mut scale = self.scale_2d();
+        if self.flip_x {
+            scale.x = -scale.x;
         }
-        // Bevy's asset system is relative from the assets/ subdirectory, so we must be too
-        let filepath = PathBuf::from("assets").join(self.collider_filepath.clone());
-        let mut fh = match File::create(filepath) {
-            Ok(fh) => fh,
-            Err(e) => {
-                eprintln!("failed creating collider file: {}", e);
-                return false;
-            }
-        };
+        if self.flip_y {
+            scale.y = -scale.y;
+        }
+        scale
+    }
 
-        let collider_ron = match ron::ser::to_string_pretty(&self.collider, Default::default()) {
-            Ok(r) => r,
-            Err(e) => {
-                eprintln!("failed converting collider to ron: {}", e);
-                return false;
-            }
+    /// Start playing the animation named `name` from its first frame, even if it was already
+    /// playing. See [`Engine::add_animation`](crate::prelude::Engine::add_animation).
+    pub fn play_animation<T: Into<String>>(&mut self, name: T) {
+        let name = name.into();
+        self.animation_state = AnimationState {
+            playing: Some(name.clone()),
+            ..Default::default()
         };
-        match fh.write_all(collider_ron.as_bytes()) {
-            Ok(_) => true,
+        self.animation = Some(name);
+        self.animation_frame = 0;
+        self.animation_paused = false;
+    }
+
+    /// Stop playing any animation, and go back to showing the sprite's normal image.
+    pub fn stop_animation(&mut self) {
+        self.animation = None;
+        self.animation_frame = 0;
+        self.animation_state = AnimationState::default();
+    }
+
+    /// Start following `path` from its beginning, replacing any path the sprite was already
+    /// following. Returns the path so you can change its settings. See [`SpritePath`].
+    pub fn follow_path(&mut self, mut path: SpritePath) -> &mut SpritePath {
+        path.distance = 0.0;
+        path.reversed = false;
+        path.finished = false;
+        self.path.insert(path)
+    }
+
+    /// The size of this sprite's image after `scale` (or `scale_xy`) is applied, ignoring
+    /// `rotation`. Returns `None` if the image hasn't finished loading yet (see
+    /// [`Sprite::image_size`]).
+    pub fn scaled_size(&self) -> Option<Vec2> {
+        self.image_size.map(|size| size * self.scale_2d().abs())
+    }
+
+    /// The smallest axis-aligned rectangle in world space that contains this sprite's image, taking
+    /// `translation`, `scale` and `rotation` into account. Handy for keeping sprites on the screen
+    /// and for placing sprites next to each other. Returns `None` if the image hasn't finished
+    /// loading yet (see [`Sprite::image_size`]).
+    pub fn aabb(&self) -> Option<Rect> {
+        let half_size = self.scaled_size()? * 0.5;
+        let (sin, cos) = self.rotation.sin_cos();
+        let half_extents = Vec2::new(
+            half_size.x * cos.abs() + half_size.y * sin.abs(),
+            half_size.x * sin.abs() + half_size.y * cos.abs(),
+        );
+        // The center of the image is away from `translation` if the anchor isn't centered
+        let center = self.translation
+            + Vec2::from_angle(self.rotation).rotate(-self.anchor_offset() * self.flipped_scale());
+        Some(Rect::from_center_half_size(center, half_extents))
+    }
+
+    /// Attempt to take the current collider and write it to collider_filepath. If there isn't a
+    /// collider, or writing fails, then `false` is returned. Otherwise `true` is returned. Use
+    /// [`Sprite::try_write_collider`] to find out what went wrong.
+    pub fn write_collider(&self) -> bool {
+        match self.try_write_collider() {
+            Ok(()) => true,
+            Err(EngineError::NoCollider { .. }) => false,
             Err(e) => {
-                eprintln!("failed writing collider file: {}", e);
+                eprintln!("{}", e);
                 false
             }
         }
     }
+
+    /// Attempt to take the current collider and write it to collider_filepath. Returns an
+    /// [`EngineError`] if there isn't a collider, or if writing fails.
+    pub fn try_write_collider(&self) -> Result<(), EngineError> {
+        if self.collider == Collider::NoCollider {
+            return Err(EngineError::NoCollider {
+                label: self.label.clone(),
+            });
+        }
+        let collider_ron = ron::ser::to_string_pretty(&self.collider, Default::default())
+            .map_err(EngineError::Serialize)?;
+        // Bevy's asset system is relative from the assets/ subdirectory, so we must be too
+        let filepath = PathBuf::from("assets").join(self.collider_filepath.clone());
+        File::create(&filepath)
+            .and_then(|mut fh| fh.write_all(collider_ron.as_bytes()))
+            .map_err(|error| EngineError::Io { filepath, error })
+    }
     /// Add a collider point. `p` is a `Vec2` in worldspace (usually the mouse coordinate). See the
     /// `collider` example.
     pub fn add_collider_point(&mut self, mut p: Vec2) {
@@ -138,18 +469,22 @@ impl Sprite {
         if self.collider == Collider::NoCollider {
             self.collider = Collider::Poly(Vec::new());
         }
+        let flipped_scale = self.flipped_scale();
+        let anchor_offset = self.anchor_offset();
         // Add the current point to the collider
         if let Collider::Poly(points) = &mut self.collider {
             // untranslate (make p relative to the sprite's position)
             p -= self.translation;
-            // unscale (make p the same scale as the sprite)
-            p *= 1.0 / self.scale;
             // unrotate (make p the same rotation as the sprite)
             let mut p2 = Vec2::ZERO;
             let sin = (-self.rotation).sin();
             let cos = (-self.rotation).cos();
             p2.x = p.x * cos - p.y * sin;
             p2.y = p.x * sin + p.y * cos;
+            // unscale and unflip (make p the same scale as the sprite)
+            p2 /= flipped_scale;
+            // unanchor (make p relative to the center of the image)
+            p2 += anchor_offset;
             points.push(p2);
         }
     }
@@ -161,6 +496,8 @@ impl Sprite {
         if self.collider == Collider::NoCollider {
             self.collider = Collider::Poly(vec![Vec2::ZERO]);
         }
+        let flipped_scale = self.flipped_scale();
+        let anchor_offset = self.anchor_offset();
         // Add the current point to the collider
         if let Collider::Poly(points) = &mut self.collider {
             // If the collider exists, but doesn't have any points, add a "last point" to modify.
@@ -169,8 +506,6 @@ impl Sprite {
             }
             // untranslate (make p relative to the sprite's origin instead of the world's origin)
             p -= self.translation;
-            // unscale (make p the same scale as the sprite)
-            p *= 1.0 / self.scale;
             // unrotate (make p the same rotation as the sprite)
             let length = points.len();
             let p2 = points.get_mut(length - 1).unwrap(); // mutable reference to "last point"
@@ -178,17 +513,60 @@ impl Sprite {
             let cos = (-self.rotation).cos();
             p2.x = p.x * cos - p.y * sin;
             p2.y = p.x * sin + p.y * cos;
+            // unscale and unflip (make p the same scale as the sprite)
+            *p2 /= flipped_scale;
+            // unanchor (make p relative to the center of the image)
+            *p2 += anchor_offset;
         }
     }
 }
 
 use std::{
     array::IntoIter,
+    collections::{BTreeMap, BTreeSet},
     fs::File,
     io::Write,
     path::{Path, PathBuf},
 };
 
+/// The point on a sprite's image that its `translation` refers to, and that it rotates, scales,
+/// and flips around. See [`Sprite::anchor`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub enum Anchor {
+    #[default]
+    Center,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    CenterLeft,
+    CenterRight,
+    TopLeft,
+    TopCenter,
+    TopRight,
+    /// A custom point, relative to the size of the image. `(0.0, 0.0)` is the center, `(-0.5,
+    /// -0.5)` is the bottom left corner, and `(0.5, 0.5)` is the top right corner. Values outside
+    /// of that range are fine, too -- the sprite will orbit around a point outside of its image.
+    Custom(Vec2),
+}
+
+impl Anchor {
+    /// The anchor point relative to the size of the image, as described in [`Anchor::Custom`]
+    pub fn as_vec(&self) -> Vec2 {
+        match self {
+            Anchor::Center => Vec2::ZERO,
+            Anchor::BottomLeft => Vec2::new(-0.5, -0.5),
+            Anchor::BottomCenter => Vec2::new(0.0, -0.5),
+            Anchor::BottomRight => Vec2::new(0.5, -0.5),
+            Anchor::CenterLeft => Vec2::new(-0.5, 0.0),
+            Anchor::CenterRight => Vec2::new(0.5, 0.0),
+            Anchor::TopLeft => Vec2::new(-0.5, 0.5),
+            Anchor::TopCenter => Vec2::new(0.0, 0.5),
+            Anchor::TopRight => Vec2::new(0.5, 0.5),
+            Anchor::Custom(point) => *point,
+        }
+    }
+}
+
 /// Sprite presets using the asset pack all have colliders
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum SpritePreset {
@@ -304,3 +682,91 @@ impl From<SpritePreset> for PathBuf {
         sprite_preset.filepath()
     }
 }
+
+/// Which sprites have which tags, so sprites can be looked up by tag without checking every sprite.
+/// The index is kept up to date as sprites are synced to Bevy at the end of each frame, and by the
+/// `Engine` methods that change tags right away. Lookups double-check each sprite's tags, so
+/// sprites that were removed or untagged since then are skipped.
+#[derive(Debug, Default)]
+pub(crate) struct TagIndex {
+    // sprite labels by tag
+    labels: HashMap<String, HashSet<String>>,
+    // sprites added since the end of the last frame, whose tags may still be changing
+    added: HashSet<String>,
+}
+
+impl TagIndex {
+    /// Keep an eye on a sprite that was just added
+    pub(crate) fn add(&mut self, label: &str) {
+        self.added.insert(label.to_string());
+    }
+
+    /// Forget the sprites added during the frame, now that their tags are in the index
+    pub(crate) fn clear_added(&mut self) {
+        self.added.clear();
+    }
+
+    /// Record the tags of a sprite that was just spawned
+    pub(crate) fn insert_sprite(&mut self, sprite: &Sprite) {
+        for tag in sprite.tags.iter() {
+            self.insert(&sprite.label, tag);
+        }
+    }
+
+    /// Forget the tags of a sprite that was just despawned
+    pub(crate) fn remove_sprite(&mut self, sprite: &Sprite) {
+        for tag in sprite.tags.iter() {
+            self.remove(&sprite.label, tag);
+        }
+    }
+
+    /// Record the tags of a sprite whose tags went from `old` to `new`
+    pub(crate) fn retag(&mut self, label: &str, old: &BTreeSet<String>, new: &BTreeSet<String>) {
+        for tag in old.difference(new) {
+            self.remove(label, tag);
+        }
+        for tag in new.difference(old) {
+            self.insert(label, tag);
+        }
+    }
+
+    /// Record that the sprite has the tag
+    pub(crate) fn insert(&mut self, label: &str, tag: &str) {
+        self.labels
+            .entry(tag.to_string())
+            .or_default()
+            .insert(label.to_string());
+    }
+
+    /// Record that the sprite no longer has the tag
+    pub(crate) fn remove(&mut self, label: &str, tag: &str) {
+        if let Some(labels) = self.labels.get_mut(tag) {
+            labels.remove(label);
+            if labels.is_empty() {
+                self.labels.remove(tag);
+            }
+        }
+    }
+
+    /// The labels of the sprites which have the tag
+    pub(crate) fn labels<'a>(
+        &'a self,
+        tag: &'a str,
+        sprites: &'a HashMap<String, Sprite>,
+    ) -> impl Iterator<Item = &'a String> + 'a {
+        let indexed = self.labels.get(tag);
+        indexed
+            .into_iter()
+            .flatten()
+            .chain(
+                self.added
+                    .iter()
+                    .filter(move |label| !indexed.is_some_and(|labels| labels.contains(*label))),
+            )
+            .filter(move |label| {
+                sprites
+                    .get(*label)
+                    .is_some_and(|sprite| sprite.has_tag(tag))
+            })
+    }
+}
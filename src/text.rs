@@ -1,5 +1,5 @@
 /// Facilities for dealing with text
-use bevy::prelude::{Component, Quat, Transform, Vec2, Vec3};
+use bevy::prelude::{Component, Quat, Transform, Vec2, Vec3, Visibility};
 
 /// Default depth of the text, positioned so it will be on top of other default layers. Depth
 /// can range from `0.0` (back) to `999.0` (front)
@@ -12,8 +12,8 @@ pub const TEXT_DEFAULT_FONT_SIZE: f32 = 30.0;
 pub struct Text {
     /// READONLY: A label to identify the text. This is not the text that is displayed! This is the
     /// label you use to retrieve and modify your text from the
-    /// [`Engine::texts`](crate::prelude::Engine::texts) HashMap. This must be *unique* or the game
-    /// will crash.
+    /// [`Engine::texts`](crate::prelude::Engine::texts) HashMap. This must be *unique*, or the text
+    /// will replace the existing text with the same label.
     pub label: String,
     /// SYNCED: The actual text value you want to display.
     pub value: String,
@@ -34,6 +34,18 @@ pub struct Text {
     pub rotation: f32,
     /// SYNCED: `1.0` is the normal 100%.
     pub scale: f32,
+    /// SYNCED: The label of the sprite this text is attached to, if any. When set, `translation`,
+    /// `rotation` and `scale` are relative to the parent sprite, so the text follows it around.
+    /// See [`Engine::text_world_transform`](crate::prelude::Engine::text_world_transform).
+    pub parent: Option<String>,
+    /// SYNCED: Whether or not the text is drawn. Defaults to `true`.
+    pub visible: bool,
+    /// READONLY: The scene that was active when this text was added with
+    /// [`Engine::add_text`](crate::prelude::Engine::add_text), if any. When that scene is exited,
+    /// this text is removed (unless it is `persistent`).
+    pub scene: Option<String>,
+    /// SYNCED: If `true`, this text is not removed when the scene it was added in is exited.
+    pub persistent: bool,
 }
 
 impl Default for Text {
@@ -47,11 +59,25 @@ impl Default for Text {
             layer: TEXT_DEFAULT_LAYER,
             rotation: f32::default(),
             scale: 1.0,
+            parent: None,
+            visible: true,
+            scene: None,
+            persistent: false,
         }
     }
 }
 
 impl Text {
+    /// Convert `visible` to Bevy's Visibility
+    #[doc(hidden)]
+    pub fn bevy_visibility(&self) -> Visibility {
+        if self.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    }
+
     #[doc(hidden)]
     pub fn bevy_transform(&self) -> Transform {
         let mut transform = Transform::from_translation(self.translation.extend(self.layer));
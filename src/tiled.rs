@@ -0,0 +1,1127 @@
+//! Facilities for importing maps made with the [Tiled](https://www.mapeditor.org/) editor. Tiled
+//! maps can be saved as JSON (`.tmj` or `.json`) or XML (`.tmx`) files. Put the map (and its
+//! tileset images) inside the `assets/` directory, then load it:
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! game.load_tiled_map("map/arena.tmj").unwrap();
+//! game.run(GameState);
+//! # }
+//! ```
+//!
+//! The map is centered on the middle of the screen, and converted like this:
+//!
+//! - Each tile layer becomes a [`Tilemap`], labeled with the layer's name (followed by the
+//!   layer's id if the name is empty or another tile layer has the same name). Tiles with a custom
+//!   `solid` property set to `true`, or with collision shapes drawn in Tiled's tile collision
+//!   editor, are solid. Setting `solid` on the layer makes all of its tiles solid. Tiles that are
+//!   flipped horizontally or vertically keep their flipping (see [`Tile::flip_x`]).
+//! - Each object in an object layer becomes a [`Sprite`], labeled with the object's name (followed
+//!   by the object's id if the name is empty or used more than once anywhere in the map). If that
+//!   label is already somebody else's, a number is added to it as well, so every label is unique.
+//!   Its position, rotation, size, flipping and visibility carry over, and its custom properties
+//!   (plus its class, as the `class` property) end up in [`Sprite::properties`]. Its class is also
+//!   added to [`Sprite::tags`].
+//! - Tile objects show the tile's image. If the tile has a collision shape in Tiled, that becomes
+//!   the sprite's collider -- otherwise the image's `.collider` file is used, like
+//!   [`Sprite::new`] does.
+//! - Polygon, rectangle and ellipse objects become hidden sprites with no image and a
+//!   [`Collider::Poly`] collider in the object's shape, which are handy for trigger zones.
+//!   Collision detection only works with convex shapes, so a concave polygon is replaced with its
+//!   convex hull (with a warning). Point objects become hidden sprites with no collider, which are
+//!   handy for spawn points.
+//! - Each layer is drawn in front of the layers before it.
+//!
+//! Tile objects have collisions turned off, and shape objects have them turned on (even though
+//! they're hidden). Set a custom `collision` property on an object to choose for yourself.
+//!
+//! Only orthogonal maps with a fixed size are supported, and tile layers must use CSV encoding
+//! (the default for JSON maps). Tile objects must come from an "image collection" tileset. Image
+//! layers are ignored. Tiles that are flipped diagonally (which is how Tiled rotates tiles) lose
+//! their diagonal flip, with a warning.
+
+use crate::{
+    error::EngineError,
+    physics::{Collider, convex_collider},
+    sprite::Sprite,
+    tilemap::{Tile, Tilemap},
+};
+use bevy::{
+    log::warn,
+    platform::collections::{HashMap, HashSet},
+    prelude::{Rect, UVec2, Vec2},
+};
+use roxmltree::Node;
+use serde::{
+    Deserialize,
+    de::{DeserializeOwned, IgnoredAny},
+};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+// Tiled stores flipping in the top bits of a tile's global id
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const GID_MASK: u32 = 0x0fff_ffff;
+
+/// Everything in a Tiled map, converted to tilemaps and sprites. Get one with
+/// [`TiledMap::read_from_file`], and add it to the game with
+/// [`Engine::add_tiled_map`](crate::prelude::Engine::add_tiled_map) -- or use
+/// [`Engine::load_tiled_map`](crate::prelude::Engine::load_tiled_map) to do both at once. See the
+/// [module documentation](crate::tiled) for how the map is converted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TiledMap {
+    /// One tilemap per tile layer
+    pub tilemaps: Vec<Tilemap>,
+    /// One sprite per object in the object layers
+    pub sprites: Vec<Sprite>,
+}
+
+impl TiledMap {
+    /// Attempt to read a Tiled map from a file inside the `assets/` directory. Files ending in
+    /// `.tmx` are read as XML, and anything else as JSON. Tilesets in separate files (`.tsx`, or
+    /// `.tsj` for JSON) are read too.
+    pub fn read_from_file<P: AsRef<Path>>(filepath: P) -> Result<Self, EngineError> {
+        let filepath = filepath.as_ref();
+        let map: MapData = read_tiled_file(filepath, xml_map)?;
+        Self::from_map_data(filepath, map)
+    }
+
+    /// Convert a map that was read from `filepath`, reading any tilesets in separate files
+    fn from_map_data(filepath: &Path, mut map: MapData) -> Result<Self, EngineError> {
+        let directory = filepath.parent().unwrap_or(Path::new("")).to_path_buf();
+        if !(map.orientation.is_empty() || map.orientation == "orthogonal") {
+            return Err(tiled_error(
+                filepath,
+                format!("{} maps aren't supported", map.orientation),
+            ));
+        }
+        if map.infinite {
+            return Err(tiled_error(filepath, "infinite maps aren't supported"));
+        }
+        for tileset in map.tilesets.iter_mut() {
+            match tileset.source.take() {
+                Some(source) => {
+                    let tileset_filepath = asset_path(&directory, &source);
+                    let first_gid = tileset.first_gid;
+                    *tileset = read_tiled_file(&tileset_filepath, xml_tileset)?;
+                    tileset.first_gid = first_gid;
+                    tileset.directory = tileset_filepath
+                        .parent()
+                        .unwrap_or(Path::new(""))
+                        .to_path_buf();
+                }
+                None => tileset.directory = directory.clone(),
+            }
+        }
+        map.tilesets.sort_by_key(|tileset| tileset.first_gid);
+
+        let mut object_names = HashMap::new();
+        let mut layer_names = HashMap::new();
+        count_names(&map.layers, &mut object_names, &mut layer_names);
+        let mut converter = Converter {
+            filepath,
+            size: Vec2::new(
+                map.width as f32 * map.tile_width,
+                map.height as f32 * map.tile_height,
+            ),
+            tile_size: Vec2::new(map.tile_width, map.tile_height),
+            tilesets: &map.tilesets,
+            depth: 0.0,
+            object_names,
+            layer_names,
+            object_labels: HashSet::new(),
+            layer_labels: HashSet::new(),
+            warned_diagonal: false,
+            tiled_map: TiledMap::default(),
+        };
+        converter.add_layers(&map.layers, Vec2::ZERO, true)?;
+        Ok(converter.tiled_map)
+    }
+}
+
+/// Turns the layers of a map into tilemaps and sprites
+struct Converter<'a> {
+    filepath: &'a Path,
+    // the size of the whole map in pixels
+    size: Vec2,
+    tile_size: Vec2,
+    tilesets: &'a [TilesetData],
+    // the layer of the next tilemap or sprites
+    depth: f32,
+    // how many times each name is used by objects and tile layers in the whole map, so that
+    // labels can be made unique
+    object_names: HashMap<String, usize>,
+    layer_names: HashMap<String, usize>,
+    // the labels handed out so far
+    object_labels: HashSet<String>,
+    layer_labels: HashSet<String>,
+    // whether we already warned about diagonally flipped tiles
+    warned_diagonal: bool,
+    tiled_map: TiledMap,
+}
+
+impl Converter<'_> {
+    /// Convert a point in Tiled's space (pixels from the top left corner of the map, with positive
+    /// y down) to world space
+    fn to_world(&self, point: Vec2) -> Vec2 {
+        Vec2::new(point.x - self.size.x * 0.5, self.size.y * 0.5 - point.y)
+    }
+
+    /// The tileset that a global tile id belongs to, and the tile's id within that tileset
+    fn tileset(&self, gid: u32) -> Result<(&TilesetData, u32), EngineError> {
+        let gid = gid & GID_MASK;
+        self.tilesets
+            .iter()
+            .rev()
+            .find(|tileset| tileset.first_gid <= gid)
+            .map(|tileset| (tileset, gid - tileset.first_gid))
+            .ok_or_else(|| tiled_error(self.filepath, format!("tile {gid} isn't in any tileset")))
+    }
+
+    fn add_layers(
+        &mut self,
+        layers: &[LayerData],
+        offset: Vec2,
+        visible: bool,
+    ) -> Result<(), EngineError> {
+        for layer in layers {
+            let offset = offset + Vec2::new(layer.offset_x, layer.offset_y);
+            let visible = visible && layer.visible;
+            match layer.kind.as_str() {
+                "tilelayer" => self.add_tile_layer(layer, offset, visible)?,
+                "objectgroup" => self.add_object_layer(layer, offset, visible)?,
+                "group" => {
+                    self.add_layers(&layer.layers, offset, visible)?;
+                    continue;
+                }
+                _ => continue,
+            }
+            self.depth += 1.0;
+        }
+        Ok(())
+    }
+
+    fn add_tile_layer(
+        &mut self,
+        layer: &LayerData,
+        offset: Vec2,
+        visible: bool,
+    ) -> Result<(), EngineError> {
+        let gids = match &layer.data {
+            Some(LayerTiles::Gids(gids)) => gids,
+            Some(LayerTiles::Encoded(_)) => {
+                return Err(tiled_error(
+                    self.filepath,
+                    format!(
+                        "tile layer {:?} isn't CSV encoded. Change its encoding in Tiled's map properties.",
+                        layer.name
+                    ),
+                ));
+            }
+            None => return Ok(()),
+        };
+        let all_solid = properties(&layer.properties).get("solid") == Some(&"true".to_string());
+        let label = unique_label(
+            &layer.name,
+            "layer",
+            layer.id,
+            &self.layer_names,
+            &mut self.layer_labels,
+        );
+        let mut tilemap = Tilemap::new(label, self.tile_size);
+        // global tile id (with its flip flags) -> index in the tilemap's tiles
+        let mut indices: HashMap<u32, usize> = HashMap::new();
+        for row in gids.chunks(layer.width.max(1) as usize) {
+            let mut cells = Vec::new();
+            for &gid in row {
+                if gid & FLIPPED_DIAGONALLY != 0 && !self.warned_diagonal {
+                    warn!(
+                        "{} has tiles that are rotated or flipped diagonally, which isn't supported",
+                        self.filepath.display()
+                    );
+                    self.warned_diagonal = true;
+                }
+                let gid = gid & (GID_MASK | FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY);
+                if gid & GID_MASK == 0 {
+                    cells.push(None);
+                    continue;
+                }
+                if !indices.contains_key(&gid) {
+                    let (tileset, id) = self.tileset(gid)?;
+                    let image = tileset.image(id).ok_or_else(|| {
+                        tiled_error(
+                            self.filepath,
+                            format!("tile {} has no image", gid & GID_MASK),
+                        )
+                    })?;
+                    let solid = all_solid
+                        || tileset.tile(id).is_some_and(|tile| {
+                            properties(&tile.properties).get("solid") == Some(&"true".to_string())
+                                || tile.collision_shape().is_some()
+                        });
+                    let mut tile = Tile::new(image.filepath, solid);
+                    tile.rect = image.rect;
+                    tile.flip_x = gid & FLIPPED_HORIZONTALLY != 0;
+                    tile.flip_y = gid & FLIPPED_VERTICALLY != 0;
+                    indices.insert(gid, tilemap.tiles().len());
+                    tilemap.tiles_mut().push(tile);
+                }
+                cells.push(indices.get(&gid).copied());
+            }
+            tilemap.grid_mut().push(cells);
+        }
+        tilemap.translation = self.to_world(offset) + tilemap.size() * Vec2::new(0.5, -0.5);
+        tilemap.layer = self.depth;
+        tilemap.visible = visible;
+        self.tiled_map.tilemaps.push(tilemap);
+        Ok(())
+    }
+
+    fn add_object_layer(
+        &mut self,
+        layer: &LayerData,
+        offset: Vec2,
+        visible: bool,
+    ) -> Result<(), EngineError> {
+        for object in &layer.objects {
+            let label = unique_label(
+                &object.name,
+                "object",
+                object.id,
+                &self.object_names,
+                &mut self.object_labels,
+            );
+            let mut sprite = self.object_sprite(label, object, offset)?;
+            sprite.layer = self.depth;
+            sprite.visible &= visible && object.visible;
+            self.tiled_map.sprites.push(sprite);
+        }
+        Ok(())
+    }
+
+    fn object_sprite(
+        &self,
+        label: String,
+        object: &ObjectData,
+        offset: Vec2,
+    ) -> Result<Sprite, EngineError> {
+        // Objects rotate (clockwise, in degrees) around their position
+        let position = self.to_world(offset + Vec2::new(object.x, object.y));
+        let rotation = -object.rotation.to_radians();
+        let mut properties = BTreeMap::new();
+
+        let mut sprite = if let Some(gid) = object.gid {
+            let (tileset, id) = self.tileset(gid)?;
+            let image = tileset
+                .image(id)
+                .filter(|image| image.rect.is_none())
+                .ok_or_else(|| {
+                    tiled_error(
+                        self.filepath,
+                        format!(
+                            "tile object {label:?} doesn't use a tile from an image collection tileset"
+                        ),
+                    )
+                })?;
+            let mut sprite = Sprite::try_new(label, image.filepath)?;
+            let size = Vec2::new(object.width, object.height);
+            let size = if size.cmpgt(Vec2::ZERO).all() {
+                size
+            } else {
+                image.size
+            };
+            // The position of a tile object is its bottom left corner
+            sprite.translation = position + Vec2::from_angle(rotation).rotate(size * 0.5);
+            if image.size.cmpgt(Vec2::ZERO).all() && size != image.size {
+                sprite.scale_xy = Some(size / image.size);
+            }
+            sprite.flip_x = gid & FLIPPED_HORIZONTALLY != 0;
+            sprite.flip_y = gid & FLIPPED_VERTICALLY != 0;
+            if let Some(tile) = tileset.tile(id) {
+                if let Some(shape) = tile.collision_shape() {
+                    // Tile collision shapes are relative to the top left corner of the image
+                    let center = Vec2::new(image.size.x, -image.size.y) * 0.5;
+                    let origin = Vec2::new(shape.x, -shape.y);
+                    let points: Vec<Vec2> = shape_points(shape)
+                        .into_iter()
+                        .map(|point| {
+                            origin + Vec2::from_angle(-shape.rotation.to_radians()).rotate(point)
+                                - center
+                        })
+                        .collect();
+                    sprite.collider = self.convex_collider(&sprite.label, points);
+                }
+                properties.extend(self::properties(&tile.properties));
+            }
+            sprite
+        } else {
+            let collider = self.convex_collider(&label, shape_points(object));
+            // Shapes have no image, so there's nothing to show
+            let mut sprite = Sprite::with_collider(label, PathBuf::new(), collider);
+            sprite.translation = position;
+            sprite.collision = !matches!(sprite.collider, Collider::NoCollider);
+            sprite.visible = false;
+            sprite.collide_while_hidden = true;
+            sprite
+        };
+        sprite.rotation = rotation;
+
+        let class = if object.class.is_empty() {
+            &object.kind
+        } else {
+            &object.class
+        };
+        if !class.is_empty() {
+            properties.insert("class".into(), class.clone());
+            sprite.tags.insert(class.clone());
+        }
+        properties.extend(self::properties(&object.properties));
+        if let Some(collision) = properties.get("collision") {
+            sprite.collision = collision == "true";
+        }
+        sprite.properties = properties;
+        Ok(sprite)
+    }
+
+    /// A collider for the outline of a shape, warning if it had to be made convex
+    fn convex_collider(&self, label: &str, points: Vec<Vec2>) -> Collider {
+        let name = format!("The shape of {label:?} in {}", self.filepath.display());
+        convex_collider(points, Some(&name))
+    }
+}
+
+/// Count how many times each name is used by the objects and by the tile layers in `layers`,
+/// including the layers inside groups
+fn count_names(
+    layers: &[LayerData],
+    object_names: &mut HashMap<String, usize>,
+    layer_names: &mut HashMap<String, usize>,
+) {
+    for layer in layers {
+        match layer.kind.as_str() {
+            "tilelayer" => *layer_names.entry(layer.name.clone()).or_default() += 1,
+            "objectgroup" => {
+                for object in &layer.objects {
+                    *object_names.entry(object.name.clone()).or_default() += 1;
+                }
+            }
+            "group" => count_names(&layer.layers, object_names, layer_names),
+            _ => {}
+        }
+    }
+}
+
+/// The label for a layer or object: its name, followed by its id if the name is empty or used
+/// more than once. A label made up that way which is also somebody's name, or which was already
+/// handed out, gets a number added until it is unique. The label is added to `used`.
+fn unique_label(
+    name: &str,
+    fallback: &str,
+    id: u32,
+    counts: &HashMap<String, usize>,
+    used: &mut HashSet<String>,
+) -> String {
+    let label = if name.is_empty() {
+        format!("{fallback}{id}")
+    } else if counts.get(name).is_some_and(|&count| count > 1) {
+        format!("{name}{id}")
+    } else {
+        name.to_string()
+    };
+    let taken = |label: &str| used.contains(label) || (label != name && counts.contains_key(label));
+    let mut unique = label.clone();
+    let mut number = 2;
+    while taken(&unique) {
+        unique = format!("{label}_{number}");
+        number += 1;
+    }
+    used.insert(unique.clone());
+    unique
+}
+
+/// The outline of a shape object, relative to the object's position, with positive y up. Ellipses
+/// are approximated with a polygon. Point objects (and any other objects) have no outline.
+fn shape_points(object: &ObjectData) -> Vec<Vec2> {
+    let size = Vec2::new(object.width, object.height);
+    if let Some(polygon) = &object.polygon {
+        polygon
+            .iter()
+            .map(|point| Vec2::new(point.x, -point.y))
+            .collect()
+    } else if object.point || size.cmple(Vec2::ZERO).any() {
+        Vec::new()
+    } else if object.ellipse {
+        const VERTICES: usize = 16;
+        let radius = size * 0.5;
+        let center = Vec2::new(radius.x, -radius.y);
+        (0..VERTICES)
+            .map(|i| {
+                let angle = std::f32::consts::TAU * i as f32 / VERTICES as f32;
+                center + Vec2::from_angle(angle) * radius
+            })
+            .collect()
+    } else {
+        vec![
+            Vec2::ZERO,
+            Vec2::new(size.x, 0.0),
+            Vec2::new(size.x, -size.y),
+            Vec2::new(0.0, -size.y),
+        ]
+    }
+}
+
+/// Custom properties, with all their values as strings
+fn properties(properties: &[PropertyData]) -> BTreeMap<String, String> {
+    properties
+        .iter()
+        .map(|property| {
+            let value = match &property.value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            (property.name.clone(), value)
+        })
+        .collect()
+}
+
+/// Resolve a path that is relative to a file in `directory` (inside `assets/`) into a path inside
+/// `assets/`
+fn asset_path(directory: &Path, relative: &str) -> PathBuf {
+    let mut path = PathBuf::new();
+    for component in directory.join(relative).components() {
+        match component {
+            Component::ParentDir => {
+                path.pop();
+            }
+            Component::CurDir => {}
+            component => path.push(component),
+        }
+    }
+    path
+}
+
+fn tiled_error<M: Display>(filepath: &Path, message: M) -> EngineError {
+    EngineError::Tiled {
+        filepath: PathBuf::from("assets").join(filepath),
+        message: message.to_string(),
+    }
+}
+
+/// Read a map or tileset from a file inside `assets/`. `.tmx` and `.tsx` files are parsed as XML
+/// with `xml`, and anything else as JSON.
+fn read_tiled_file<T: DeserializeOwned>(
+    filepath: &Path,
+    xml: fn(Node, &Path) -> Result<T, EngineError>,
+) -> Result<T, EngineError> {
+    let actual_filepath = PathBuf::from("assets").join(filepath);
+    let contents = std::fs::read_to_string(&actual_filepath).map_err(|error| EngineError::Io {
+        filepath: actual_filepath,
+        error,
+    })?;
+    parse_tiled_file(filepath, &contents, xml)
+}
+
+/// Parse the contents of a map or tileset file, the same way as [`read_tiled_file`]
+fn parse_tiled_file<T: DeserializeOwned>(
+    filepath: &Path,
+    contents: &str,
+    xml: fn(Node, &Path) -> Result<T, EngineError>,
+) -> Result<T, EngineError> {
+    if filepath
+        .extension()
+        .is_some_and(|ext| ext == "tmx" || ext == "tsx")
+    {
+        let document =
+            roxmltree::Document::parse(contents).map_err(|error| tiled_error(filepath, error))?;
+        xml(document.root_element(), filepath)
+    } else {
+        serde_json::from_str(contents).map_err(|error| tiled_error(filepath, error))
+    }
+}
+
+// The parts of Tiled's file format that we use. The JSON format is deserialized directly, and the
+// XML format is converted into the same structures.
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct MapData {
+    width: u32,
+    height: u32,
+    #[serde(rename = "tilewidth")]
+    tile_width: f32,
+    #[serde(rename = "tileheight")]
+    tile_height: f32,
+    #[serde(default)]
+    orientation: String,
+    #[serde(default)]
+    infinite: bool,
+    #[serde(default)]
+    tilesets: Vec<TilesetData>,
+    #[serde(default)]
+    layers: Vec<LayerData>,
+}
+
+#[derive(Default, Deserialize)]
+struct TilesetData {
+    #[serde(default, rename = "firstgid")]
+    first_gid: u32,
+    // set if the tileset is in a separate file
+    source: Option<String>,
+    // set if all the tiles are parts of a single image
+    image: Option<String>,
+    #[serde(default)]
+    columns: u32,
+    #[serde(default, rename = "tilewidth")]
+    tile_width: f32,
+    #[serde(default, rename = "tileheight")]
+    tile_height: f32,
+    #[serde(default)]
+    margin: f32,
+    #[serde(default)]
+    spacing: f32,
+    #[serde(default)]
+    tiles: Vec<TileData>,
+    // the directory (inside `assets/`) that paths in the tileset are relative to
+    #[serde(skip)]
+    directory: PathBuf,
+}
+
+/// The image for a tile
+struct TileImage {
+    filepath: PathBuf,
+    // the part of the image that is the tile, if the tileset is a single image
+    rect: Option<Rect>,
+    size: Vec2,
+}
+
+impl TilesetData {
+    fn tile(&self, id: u32) -> Option<&TileData> {
+        self.tiles.iter().find(|tile| tile.id == id)
+    }
+
+    fn image(&self, id: u32) -> Option<TileImage> {
+        if let Some(image) = &self.image {
+            let columns = self.columns.max(1);
+            let size = Vec2::new(self.tile_width, self.tile_height);
+            let min = Vec2::splat(self.margin)
+                + UVec2::new(id % columns, id / columns).as_vec2() * (size + self.spacing);
+            return Some(TileImage {
+                filepath: asset_path(&self.directory, image),
+                rect: Some(Rect::from_corners(min, min + size)),
+                size,
+            });
+        }
+        let tile = self.tile(id)?;
+        Some(TileImage {
+            filepath: asset_path(&self.directory, tile.image.as_ref()?),
+            rect: None,
+            size: Vec2::new(tile.image_width, tile.image_height),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct TileData {
+    id: u32,
+    image: Option<String>,
+    #[serde(default, rename = "imagewidth")]
+    image_width: f32,
+    #[serde(default, rename = "imageheight")]
+    image_height: f32,
+    #[serde(default)]
+    properties: Vec<PropertyData>,
+    // collision shapes
+    #[serde(rename = "objectgroup")]
+    object_group: Option<LayerData>,
+}
+
+impl TileData {
+    /// The first of the tile's collision shapes, if any
+    fn collision_shape(&self) -> Option<&ObjectData> {
+        self.object_group.as_ref()?.objects.first()
+    }
+}
+
+#[derive(Deserialize)]
+struct LayerData {
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    id: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    width: u32,
+    data: Option<LayerTiles>,
+    #[serde(default, rename = "offsetx")]
+    offset_x: f32,
+    #[serde(default, rename = "offsety")]
+    offset_y: f32,
+    #[serde(default = "default_true")]
+    visible: bool,
+    #[serde(default)]
+    properties: Vec<PropertyData>,
+    #[serde(default)]
+    objects: Vec<ObjectData>,
+    // the layers inside a group layer
+    #[serde(default)]
+    layers: Vec<LayerData>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LayerTiles {
+    Gids(Vec<u32>),
+    // base64 and/or compressed, which we don't support
+    Encoded(IgnoredAny),
+}
+
+#[derive(Deserialize)]
+struct ObjectData {
+    #[serde(default)]
+    id: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    class: String,
+    #[serde(default)]
+    x: f32,
+    #[serde(default)]
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    #[serde(default)]
+    rotation: f32,
+    gid: Option<u32>,
+    polygon: Option<Vec<PointData>>,
+    #[serde(default)]
+    ellipse: bool,
+    #[serde(default)]
+    point: bool,
+    #[serde(default = "default_true")]
+    visible: bool,
+    #[serde(default)]
+    properties: Vec<PropertyData>,
+}
+
+#[derive(Deserialize)]
+struct PointData {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Deserialize)]
+struct PropertyData {
+    name: String,
+    value: Value,
+}
+
+// Converting Tiled's XML format
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn attribute<T: FromStr>(node: Node, name: &str) -> Option<T> {
+    node.attribute(name)?.parse().ok()
+}
+
+fn string_attribute(node: Node, name: &str) -> String {
+    node.attribute(name).unwrap_or_default().to_string()
+}
+
+fn xml_map(node: Node, filepath: &Path) -> Result<MapData, EngineError> {
+    if !node.has_tag_name("map") {
+        return Err(tiled_error(filepath, "this is not a Tiled map"));
+    }
+    Ok(MapData {
+        width: attribute(node, "width").unwrap_or_default(),
+        height: attribute(node, "height").unwrap_or_default(),
+        tile_width: attribute(node, "tilewidth").unwrap_or_default(),
+        tile_height: attribute(node, "tileheight").unwrap_or_default(),
+        orientation: string_attribute(node, "orientation"),
+        infinite: attribute(node, "infinite") == Some(1),
+        tilesets: node
+            .children()
+            .filter(|child| child.has_tag_name("tileset"))
+            .map(|child| xml_tileset(child, filepath))
+            .collect::<Result<_, _>>()?,
+        layers: xml_layers(node, filepath)?,
+    })
+}
+
+fn xml_tileset(node: Node, filepath: &Path) -> Result<TilesetData, EngineError> {
+    if !node.has_tag_name("tileset") {
+        return Err(tiled_error(filepath, "this is not a Tiled tileset"));
+    }
+    Ok(TilesetData {
+        first_gid: attribute(node, "firstgid").unwrap_or_default(),
+        source: node.attribute("source").map(String::from),
+        image: child(node, "image")
+            .and_then(|image| image.attribute("source"))
+            .map(String::from),
+        columns: attribute(node, "columns").unwrap_or_default(),
+        tile_width: attribute(node, "tilewidth").unwrap_or_default(),
+        tile_height: attribute(node, "tileheight").unwrap_or_default(),
+        margin: attribute(node, "margin").unwrap_or_default(),
+        spacing: attribute(node, "spacing").unwrap_or_default(),
+        tiles: node
+            .children()
+            .filter(|child| child.has_tag_name("tile"))
+            .map(|child| xml_tile(child, filepath))
+            .collect::<Result<_, _>>()?,
+        directory: PathBuf::new(),
+    })
+}
+
+fn xml_tile(node: Node, filepath: &Path) -> Result<TileData, EngineError> {
+    let image = child(node, "image");
+    Ok(TileData {
+        id: attribute(node, "id").unwrap_or_default(),
+        image: image.and_then(|image| image.attribute("source").map(String::from)),
+        image_width: image
+            .and_then(|image| attribute(image, "width"))
+            .unwrap_or_default(),
+        image_height: image
+            .and_then(|image| attribute(image, "height"))
+            .unwrap_or_default(),
+        properties: xml_properties(node),
+        object_group: child(node, "objectgroup")
+            .map(|group| xml_layer(group, filepath))
+            .transpose()?,
+    })
+}
+
+fn xml_layers(node: Node, filepath: &Path) -> Result<Vec<LayerData>, EngineError> {
+    node.children()
+        .filter(|child| {
+            ["layer", "objectgroup", "group", "imagelayer"]
+                .iter()
+                .any(|name| child.has_tag_name(*name))
+        })
+        .map(|child| xml_layer(child, filepath))
+        .collect()
+}
+
+fn xml_layer(node: Node, filepath: &Path) -> Result<LayerData, EngineError> {
+    let data = match child(node, "data") {
+        Some(data) => Some(match data.attribute("encoding") {
+            Some("csv") => LayerTiles::Gids(
+                data.text()
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|gid| !gid.is_empty())
+                    .map(|gid| {
+                        gid.parse()
+                            .map_err(|_| tiled_error(filepath, format!("{gid:?} is not a tile id")))
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            Some(_) => LayerTiles::Encoded(IgnoredAny),
+            None => LayerTiles::Gids(
+                data.children()
+                    .filter(|child| child.has_tag_name("tile"))
+                    .map(|tile| attribute(tile, "gid").unwrap_or_default())
+                    .collect(),
+            ),
+        }),
+        None => None,
+    };
+    Ok(LayerData {
+        kind: match node.tag_name().name() {
+            "layer" => "tilelayer".to_string(),
+            name => name.to_string(),
+        },
+        id: attribute(node, "id").unwrap_or_default(),
+        name: string_attribute(node, "name"),
+        width: attribute(node, "width").unwrap_or_default(),
+        data,
+        offset_x: attribute(node, "offsetx").unwrap_or_default(),
+        offset_y: attribute(node, "offsety").unwrap_or_default(),
+        visible: attribute(node, "visible") != Some(0),
+        properties: xml_properties(node),
+        objects: node
+            .children()
+            .filter(|child| child.has_tag_name("object"))
+            .map(xml_object)
+            .collect(),
+        layers: xml_layers(node, filepath)?,
+    })
+}
+
+fn xml_object(node: Node) -> ObjectData {
+    ObjectData {
+        id: attribute(node, "id").unwrap_or_default(),
+        name: string_attribute(node, "name"),
+        kind: string_attribute(node, "type"),
+        class: string_attribute(node, "class"),
+        x: attribute(node, "x").unwrap_or_default(),
+        y: attribute(node, "y").unwrap_or_default(),
+        width: attribute(node, "width").unwrap_or_default(),
+        height: attribute(node, "height").unwrap_or_default(),
+        rotation: attribute(node, "rotation").unwrap_or_default(),
+        gid: attribute(node, "gid"),
+        polygon: child(node, "polygon").map(|polygon| {
+            polygon
+                .attribute("points")
+                .unwrap_or_default()
+                .split_whitespace()
+                .filter_map(|point| {
+                    let (x, y) = point.split_once(',')?;
+                    Some(PointData {
+                        x: x.parse().ok()?,
+                        y: y.parse().ok()?,
+                    })
+                })
+                .collect()
+        }),
+        ellipse: child(node, "ellipse").is_some(),
+        point: child(node, "point").is_some(),
+        visible: attribute(node, "visible") != Some(0),
+        properties: xml_properties(node),
+    }
+}
+
+fn xml_properties(node: Node) -> Vec<PropertyData> {
+    let Some(properties) = child(node, "properties") else {
+        return Vec::new();
+    };
+    properties
+        .children()
+        .filter(|child| child.has_tag_name("property"))
+        .map(|property| PropertyData {
+            name: string_attribute(property, "name"),
+            // Multi-line strings are stored as text instead of in the value attribute
+            value: Value::String(
+                property
+                    .attribute("value")
+                    .or_else(|| property.text())
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The same map in both formats. It has two tile layers and two objects with the same names,
+    // tiles flipped every which way, and a concave polygon.
+    const JSON_MAP: &str = r#"{
+        "width": 2, "height": 1, "tilewidth": 64, "tileheight": 64,
+        "orientation": "orthogonal", "infinite": false,
+        "tilesets": [{
+            "firstgid": 1,
+            "tiles": [
+                {
+                    "id": 0, "image": "../sprite/rolling/block_square.png",
+                    "imagewidth": 64, "imageheight": 64,
+                    "properties": [{"name": "solid", "type": "bool", "value": true}]
+                },
+                {
+                    "id": 1, "image": "../sprite/racing/car_red.png",
+                    "imagewidth": 120, "imageheight": 70
+                }
+            ]
+        }],
+        "layers": [
+            {"type": "tilelayer", "id": 1, "name": "ground", "width": 2, "data": [1, 2147483649]},
+            {"type": "tilelayer", "id": 2, "name": "ground", "width": 2, "data": [3221225473, 536870913]},
+            {"type": "objectgroup", "id": 3, "name": "pickups", "objects": [
+                {"id": 1, "name": "coin", "x": 10, "y": 20, "point": true},
+                {"id": 2, "name": "door", "x": 0, "y": 0, "width": 32, "height": 64}
+            ]},
+            {"type": "group", "id": 4, "name": "more", "layers": [
+                {"type": "objectgroup", "id": 5, "name": "things", "objects": [
+                    {"id": 3, "name": "coin", "x": 30, "y": 40, "point": true},
+                    {"id": 4, "name": "car", "gid": 3221225474, "x": 0, "y": 64,
+                        "width": 120, "height": 70},
+                    {"id": 5, "name": "zone", "x": 0, "y": 0, "polygon": [
+                        {"x": 0, "y": 0}, {"x": 64, "y": 0}, {"x": 64, "y": 32},
+                        {"x": 32, "y": 32}, {"x": 32, "y": 64}, {"x": 0, "y": 64}
+                    ]}
+                ]}
+            ]}
+        ]
+    }"#;
+
+    const XML_MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+    <map orientation="orthogonal" width="2" height="1" tilewidth="64" tileheight="64" infinite="0">
+     <tileset firstgid="1">
+      <tile id="0">
+       <properties>
+        <property name="solid" type="bool" value="true"/>
+       </properties>
+       <image source="../sprite/rolling/block_square.png" width="64" height="64"/>
+      </tile>
+      <tile id="1">
+       <image source="../sprite/racing/car_red.png" width="120" height="70"/>
+      </tile>
+     </tileset>
+     <layer id="1" name="ground" width="2" height="1">
+      <data encoding="csv">1,2147483649</data>
+     </layer>
+     <layer id="2" name="ground" width="2" height="1">
+      <data encoding="csv">3221225473,536870913</data>
+     </layer>
+     <objectgroup id="3" name="pickups">
+      <object id="1" name="coin" x="10" y="20"><point/></object>
+      <object id="2" name="door" x="0" y="0" width="32" height="64"/>
+     </objectgroup>
+     <group id="4" name="more">
+      <objectgroup id="5" name="things">
+       <object id="3" name="coin" x="30" y="40"><point/></object>
+       <object id="4" name="car" gid="3221225474" x="0" y="64" width="120" height="70"/>
+       <object id="5" name="zone" x="0" y="0">
+        <polygon points="0,0 64,0 64,32 32,32 32,64 0,64"/>
+       </object>
+      </objectgroup>
+     </group>
+    </map>"#;
+
+    fn read(filepath: &str, contents: &str) -> TiledMap {
+        let filepath = Path::new(filepath);
+        let map = parse_tiled_file(filepath, contents, xml_map).unwrap();
+        TiledMap::from_map_data(filepath, map).unwrap()
+    }
+
+    fn sprite<'a>(map: &'a TiledMap, label: &str) -> &'a Sprite {
+        map.sprites
+            .iter()
+            .find(|sprite| sprite.label == label)
+            .unwrap_or_else(|| panic!("no sprite labeled {label:?}"))
+    }
+
+    fn check(map: &TiledMap) {
+        // Duplicate names get the id added, even in different layers
+        let labels: Vec<&str> = map.tilemaps.iter().map(|t| t.label.as_str()).collect();
+        assert_eq!(labels, ["ground1", "ground2"]);
+        let labels: Vec<&str> = map.sprites.iter().map(|s| s.label.as_str()).collect();
+        assert_eq!(labels, ["coin1", "door", "coin3", "car", "zone"]);
+
+        // A flipped tile is a separate kind of tile that keeps its flipping
+        let ground = &map.tilemaps[0];
+        assert_eq!(ground.tiles().len(), 2);
+        for tile in ground.tiles() {
+            assert!(tile.solid);
+            assert_eq!(
+                tile.filepath,
+                PathBuf::from("sprite/rolling/block_square.png")
+            );
+        }
+        assert!(!ground.tiles()[0].flip_x && !ground.tiles()[0].flip_y);
+        assert!(ground.tiles()[1].flip_x && !ground.tiles()[1].flip_y);
+        assert_eq!(ground.grid(), [vec![Some(0), Some(1)]]);
+        // Flipped both ways, and flipped diagonally, which is ignored
+        let ground = &map.tilemaps[1];
+        assert_eq!(ground.grid(), [vec![Some(0), Some(1)]]);
+        assert!(ground.tiles()[0].flip_x && ground.tiles()[0].flip_y);
+        assert!(!ground.tiles()[1].flip_x && !ground.tiles()[1].flip_y);
+
+        let car = sprite(map, "car");
+        assert!(car.flip_x && car.flip_y);
+        assert_eq!(car.filepath, PathBuf::from("sprite/racing/car_red.png"));
+        assert!(!sprite(map, "door").flip_x);
+
+        let coin = sprite(map, "coin1");
+        assert_eq!(coin.translation, Vec2::new(-54.0, 12.0));
+        assert_eq!(coin.collider, Collider::NoCollider);
+
+        // The concave L shape becomes its convex hull, without the inside corner
+        let zone = sprite(map, "zone");
+        assert!(zone.collider.is_convex());
+        let Collider::Poly(points) = &zone.collider else {
+            panic!("zone has no polygon collider");
+        };
+        assert_eq!(points.len(), 5);
+        assert!(!points.contains(&Vec2::new(32.0, -32.0)));
+        assert!(points.contains(&Vec2::new(64.0, -32.0)));
+        assert!(zone.collision);
+    }
+
+    #[test]
+    fn reads_json() {
+        check(&read("map/test.tmj", JSON_MAP));
+    }
+
+    #[test]
+    fn reads_xml() {
+        check(&read("map/test.tmx", XML_MAP));
+    }
+
+    #[test]
+    fn formats_match() {
+        assert_eq!(
+            read("map/test.tmj", JSON_MAP),
+            read("map/test.tmx", XML_MAP)
+        );
+    }
+
+    #[test]
+    fn made_up_labels_never_take_a_name() {
+        // "coin" is used twice, and one of them has the id 1, but an object is named "coin1"
+        let counts = HashMap::from([("coin".to_string(), 2), ("coin1".to_string(), 1)]);
+        let mut used = HashSet::new();
+        let labels = [
+            unique_label("coin", "object", 1, &counts, &mut used),
+            unique_label("coin1", "object", 2, &counts, &mut used),
+            unique_label("coin", "object", 1, &counts, &mut used),
+            unique_label("", "object", 3, &counts, &mut used),
+        ];
+        assert_eq!(labels, ["coin1_2", "coin1", "coin1_3", "object3"]);
+    }
+
+    #[test]
+    fn convex_polygons_are_kept() {
+        let square = vec![
+            Vec2::ZERO,
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, -10.0),
+            Vec2::new(0.0, -10.0),
+        ];
+        let converter = Converter {
+            filepath: Path::new("map/test.tmj"),
+            size: Vec2::ZERO,
+            tile_size: Vec2::ZERO,
+            tilesets: &[],
+            depth: 0.0,
+            object_names: HashMap::new(),
+            layer_names: HashMap::new(),
+            object_labels: HashSet::new(),
+            layer_labels: HashSet::new(),
+            warned_diagonal: false,
+            tiled_map: TiledMap::default(),
+        };
+        assert_eq!(
+            converter.convex_collider("square", square.clone()),
+            Collider::Poly(square)
+        );
+        assert_eq!(
+            converter.convex_collider("line", vec![Vec2::ZERO, Vec2::ONE]),
+            Collider::NoCollider
+        );
+    }
+}
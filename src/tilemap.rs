@@ -0,0 +1,600 @@
+//! Facilities for building levels out of a grid of tiles. A [`Tilemap`] places an image in each
+//! cell of a grid, all as one unit, so a maze with hundreds of walls doesn't need hundreds of
+//! individually labeled sprites. Tiles can be solid, in which case sprites collide with them.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! let maze = game.add_tilemap("maze", Vec2::new(64.0, 64.0));
+//! // Tile 0 is a solid wall, tile 1 is a cone you can drive through
+//! maze.set_tiles(vec![
+//!     Tile::new(SpritePreset::RacingBarrierRed, true),
+//!     Tile::new(SpritePreset::RacingConeStraight, false),
+//! ]);
+//! // Empty cells are -1 (or left blank)
+//! maze.set_grid(
+//!     Tilemap::grid_from_csv(
+//!         "0, 0, 0, 0
+//!          0,-1, 1, 0
+//!          0, 0, 0, 0",
+//!     )
+//!     .unwrap(),
+//! );
+//! game.run(GameState);
+//! # }
+//! ```
+//!
+//! Collision events between a sprite and a tilemap use the tilemap's label, and happen when the
+//! sprite starts (or stops) touching any of the solid tiles.
+
+use crate::{error::EngineError, physics::polygons_colliding, prelude::Engine};
+use bevy::{
+    asset::RenderAssetUsages,
+    mesh::{Indices, PrimitiveTopology},
+    platform::collections::HashSet,
+    prelude::*,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::{Path, PathBuf};
+
+pub(crate) struct TilemapPlugin;
+
+impl Plugin for TilemapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, sync_tilemaps);
+    }
+}
+
+/// A kind of tile that can be placed in a [`Tilemap`]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Tile {
+    /// The image to show in each cell this tile is placed in. It is stretched to the size of the
+    /// cell.
+    pub filepath: PathBuf,
+    /// Whether sprites collide with this tile
+    pub solid: bool,
+    /// The part of the image to show, in pixels from its top left corner, or `None` for the whole
+    /// image. This lets many tiles share a single "sprite sheet" image.
+    #[serde(default)]
+    pub rect: Option<Rect>,
+    /// Whether the image is mirrored horizontally. Only the image is flipped -- a solid tile still
+    /// fills its whole cell.
+    #[serde(default)]
+    pub flip_x: bool,
+    /// Whether the image is mirrored vertically
+    #[serde(default)]
+    pub flip_y: bool,
+}
+
+impl Tile {
+    /// Create a tile from an image file in the `assets/` directory or a [`SpritePreset`](crate::prelude::SpritePreset)
+    pub fn new<P: Into<PathBuf>>(filepath: P, solid: bool) -> Self {
+        Self {
+            filepath: filepath.into(),
+            solid,
+            rect: None,
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+}
+
+/// A grid of tiles that is drawn (and collides) as a single unit. Create one with
+/// [`Engine::add_tilemap`](crate::prelude::Engine::add_tilemap). The tilemap is aligned with the
+/// axes of the world; it can't be rotated or scaled.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Tilemap {
+    /// READONLY: A label to identify the tilemap. This must be unique among tilemaps, or the
+    /// tilemap will replace the existing tilemap with the same label. Collision events use this
+    /// label, so it shouldn't be the same as the label of a sprite.
+    pub label: String,
+    /// SYNCED: The kinds of tiles that can be placed in the grid. Use [`Tilemap::tiles`],
+    /// [`Tilemap::tiles_mut`], or [`Tilemap::set_tiles`] to access it.
+    tiles: Vec<Tile>,
+    /// SYNCED: The grid of cells. Use [`Tilemap::grid`], [`Tilemap::grid_mut`],
+    /// [`Tilemap::set_grid`], or [`Tilemap::set_tile`] to access it.
+    grid: Vec<Vec<Option<usize>>>,
+    /// SYNCED: The size of each cell in world space. Use [`Tilemap::tile_size`] or
+    /// [`Tilemap::set_tile_size`] to access it.
+    #[serde(deserialize_with = "deserialize_tile_size")]
+    tile_size: Vec2,
+    /// SYNCED: Where the center of the tilemap is in 2D game space
+    pub translation: Vec2,
+    /// SYNCED: Depth of the tilemap. 0.0 (back) to 999.0 (front). Defaults to `0.0`.
+    pub layer: f32,
+    /// SYNCED: Whether or not the tilemap is drawn. Defaults to `true`. Hidden tilemaps still
+    /// collide, which is handy for an invisible layer of walls.
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    /// SYNCED: Whether or not to calculate collisions between sprites and the solid tiles.
+    /// Defaults to `true`.
+    pub collision: bool,
+    /// READONLY: The scene that was active when this tilemap was added with
+    /// [`Engine::add_tilemap`](crate::prelude::Engine::add_tilemap), if any. When that scene is
+    /// exited, this tilemap is removed (unless it is `persistent`).
+    #[serde(default)]
+    pub scene: Option<String>,
+    /// SYNCED: If `true`, this tilemap is not removed when the scene it was added in is exited.
+    #[serde(default)]
+    pub persistent: bool,
+    /// Whether the tiles, grid, or tile size changed since they were last sent to Bevy
+    #[serde(skip, default = "default_true")]
+    changed: bool,
+}
+
+/// The width or height used in place of one that isn't a positive number
+const DEFAULT_TILE_SIZE: f32 = 64.0;
+
+/// Replace any part of `tile_size` that isn't a positive number, since a cell with no size can't be
+/// drawn, and finding the cell at a point would divide by zero
+fn valid_tile_size(tile_size: Vec2) -> Vec2 {
+    let valid = |size: f32| {
+        if size.is_finite() && size > 0.0 {
+            size
+        } else {
+            DEFAULT_TILE_SIZE
+        }
+    };
+    let valid_size = Vec2::new(valid(tile_size.x), valid(tile_size.y));
+    if valid_size != tile_size {
+        warn!("Tile size {tile_size} is not a positive size, using {valid_size} instead");
+    }
+    valid_size
+}
+
+fn deserialize_tile_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec2, D::Error> {
+    Vec2::deserialize(deserializer).map(valid_tile_size)
+}
+
+impl Tilemap {
+    /// Create an empty tilemap. Usually you want
+    /// [`Engine::add_tilemap`](crate::prelude::Engine::add_tilemap) instead. A width or height in
+    /// `tile_size` that isn't a positive number is replaced with `64.0`, with a warning.
+    pub fn new<T: Into<String>>(label: T, tile_size: Vec2) -> Self {
+        Self {
+            label: label.into(),
+            tiles: Vec::new(),
+            grid: Vec::new(),
+            tile_size: valid_tile_size(tile_size),
+            translation: Vec2::ZERO,
+            layer: 0.0,
+            visible: true,
+            collision: true,
+            scene: None,
+            persistent: false,
+            changed: true,
+        }
+    }
+
+    /// The kinds of tiles that can be placed in the grid
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// Mutable access to the kinds of tiles that can be placed in the grid
+    pub fn tiles_mut(&mut self) -> &mut Vec<Tile> {
+        self.changed = true;
+        &mut self.tiles
+    }
+
+    /// Replace the kinds of tiles that can be placed in the grid
+    pub fn set_tiles(&mut self, tiles: Vec<Tile>) {
+        self.tiles = tiles;
+        self.changed = true;
+    }
+
+    /// The grid of cells, as rows from top to bottom, each row from left to right. Each cell holds
+    /// the index of a tile in [`Tilemap::tiles`], or `None` for an empty cell. Rows don't need to
+    /// be the same length.
+    pub fn grid(&self) -> &[Vec<Option<usize>>] {
+        &self.grid
+    }
+
+    /// Mutable access to the grid of cells. To change a single cell, [`Tilemap::set_tile`] is
+    /// simpler.
+    pub fn grid_mut(&mut self) -> &mut Vec<Vec<Option<usize>>> {
+        self.changed = true;
+        &mut self.grid
+    }
+
+    /// Replace the grid of cells, for example with one from [`Tilemap::grid_from_csv`]
+    pub fn set_grid(&mut self, grid: Vec<Vec<Option<usize>>>) {
+        self.grid = grid;
+        self.changed = true;
+    }
+
+    /// The size of each cell in world space
+    pub fn tile_size(&self) -> Vec2 {
+        self.tile_size
+    }
+
+    /// Change the size of each cell in world space. A width or height that isn't a positive number
+    /// is replaced with `64.0`, with a warning.
+    pub fn set_tile_size(&mut self, tile_size: Vec2) {
+        self.tile_size = valid_tile_size(tile_size);
+        self.changed = true;
+    }
+
+    /// Parse a grid from comma-separated values, one row per line. Each value is the index of a
+    /// tile. Negative or blank values are empty cells, and blank lines are skipped.
+    pub fn grid_from_csv(csv: &str) -> Result<Vec<Vec<Option<usize>>>, EngineError> {
+        let mut grid = Vec::new();
+        for (row, line) in csv
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+        {
+            let mut cells = Vec::new();
+            for (column, value) in line.split(',').enumerate() {
+                let value = value.trim();
+                if value.is_empty() {
+                    cells.push(None);
+                    continue;
+                }
+                let index = value.parse::<i64>().map_err(|_| EngineError::InvalidTile {
+                    row,
+                    column,
+                    value: value.to_string(),
+                })?;
+                cells.push(usize::try_from(index).ok());
+            }
+            grid.push(cells);
+        }
+        Ok(grid)
+    }
+
+    /// Attempt to read a grid from a file. Files ending in `.ron` should contain a list of rows,
+    /// each of which is a list of tile indices, like `[[0, 0, 0], [0, -1, 0]]`. Any other file is
+    /// read as comma-separated values (see [`Tilemap::grid_from_csv`]). Negative values are empty
+    /// cells.
+    pub fn read_grid_from_file<P: AsRef<Path>>(
+        filepath: P,
+    ) -> Result<Vec<Vec<Option<usize>>>, EngineError> {
+        let filepath = filepath.as_ref();
+        let contents = std::fs::read_to_string(filepath).map_err(|error| EngineError::Io {
+            filepath: filepath.to_path_buf(),
+            error,
+        })?;
+        if filepath.extension().is_some_and(|ext| ext == "ron") {
+            let rows =
+                ron::from_str::<Vec<Vec<i64>>>(&contents).map_err(|error| EngineError::Parse {
+                    filepath: filepath.to_path_buf(),
+                    error: Box::new(error),
+                })?;
+            Ok(rows
+                .into_iter()
+                .map(|row| row.into_iter().map(|i| usize::try_from(i).ok()).collect())
+                .collect())
+        } else {
+            Self::grid_from_csv(&contents)
+        }
+    }
+
+    /// The number of columns and rows in the grid
+    pub fn dimensions(&self) -> UVec2 {
+        let columns = self.grid.iter().map(Vec::len).max().unwrap_or(0);
+        UVec2::new(columns as u32, self.grid.len() as u32)
+    }
+
+    /// The size of the whole tilemap in world space
+    pub fn size(&self) -> Vec2 {
+        self.dimensions().as_vec2() * self.tile_size
+    }
+
+    /// The world space position of the top left corner of the tilemap
+    fn top_left(&self) -> Vec2 {
+        let size = self.size();
+        self.translation + Vec2::new(-size.x, size.y) * 0.5
+    }
+
+    /// Convert a point in world space to (fractional) columns and rows from the top left corner
+    fn cell_space(&self, point: Vec2) -> Vec2 {
+        (point - self.top_left()) * Vec2::new(1.0, -1.0) / self.tile_size
+    }
+
+    /// The cell (column, row) that contains a point in world space, or `None` if the point is
+    /// outside of the tilemap. Handy for finding out which cell the mouse or a sprite is in.
+    pub fn world_to_cell(&self, point: Vec2) -> Option<UVec2> {
+        let offset = self.cell_space(point);
+        let dimensions = self.dimensions().as_vec2();
+        if offset.x < 0.0 || offset.y < 0.0 || offset.x >= dimensions.x || offset.y >= dimensions.y
+        {
+            return None;
+        }
+        Some(offset.floor().as_uvec2())
+    }
+
+    /// The world space position of the center of a cell
+    pub fn cell_to_world(&self, cell: UVec2) -> Vec2 {
+        self.top_left() + (cell.as_vec2() + 0.5) * self.tile_size * Vec2::new(1.0, -1.0)
+    }
+
+    /// The index (into [`Tilemap::tiles`]) of the tile in a cell, or `None` if the cell is empty or outside of
+    /// the tilemap
+    pub fn index_at(&self, cell: UVec2) -> Option<usize> {
+        *self.grid.get(cell.y as usize)?.get(cell.x as usize)?
+    }
+
+    /// The tile in a cell, or `None` if the cell is empty or outside of the tilemap
+    pub fn tile_at(&self, cell: UVec2) -> Option<&Tile> {
+        self.tiles.get(self.index_at(cell)?)
+    }
+
+    /// Put a tile in a cell (or empty it with `None`). The grid grows to fit the cell if needed.
+    pub fn set_tile(&mut self, cell: UVec2, tile: Option<usize>) {
+        let (column, row) = (cell.x as usize, cell.y as usize);
+        if self.grid.len() <= row {
+            self.grid.resize(row + 1, Vec::new());
+        }
+        let cells = &mut self.grid[row];
+        if cells.len() <= column {
+            cells.resize(column + 1, None);
+        }
+        cells[column] = tile;
+        self.changed = true;
+    }
+
+    /// Whether the cell holds a solid tile
+    pub fn is_solid(&self, cell: UVec2) -> bool {
+        self.tile_at(cell).is_some_and(|tile| tile.solid)
+    }
+
+    /// Whether there is a solid tile at a point in world space
+    pub fn solid_at(&self, point: Vec2) -> bool {
+        self.world_to_cell(point)
+            .is_some_and(|cell| self.is_solid(cell))
+    }
+
+    /// Whether a polygon in world space overlaps any of the solid tiles
+    pub(crate) fn colliding(&self, poly: &[Vec2]) -> bool {
+        if poly.is_empty() {
+            return false;
+        }
+        // Only look at the cells underneath the polygon's bounding box
+        let (min, max) = poly.iter().fold((poly[0], poly[0]), |(min, max), &p| {
+            (min.min(p), max.max(p))
+        });
+        let dimensions = self.dimensions().as_ivec2();
+        let first = self
+            .cell_space(Vec2::new(min.x, max.y))
+            .floor()
+            .as_ivec2()
+            .max(IVec2::ZERO);
+        let last = self
+            .cell_space(Vec2::new(max.x, min.y))
+            .floor()
+            .as_ivec2()
+            .min(dimensions - 1);
+        let half = self.tile_size * 0.5;
+        for row in first.y..=last.y {
+            for column in first.x..=last.x {
+                let cell = UVec2::new(column as u32, row as u32);
+                if !self.is_solid(cell) {
+                    continue;
+                }
+                let center = self.cell_to_world(cell);
+                let tile_poly = [
+                    center + Vec2::new(-half.x, half.y),
+                    center + half,
+                    center + Vec2::new(half.x, -half.y),
+                    center - half,
+                ];
+                if polygons_colliding(poly, &tile_poly) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Do the math to convert to Bevy's Transform
+    fn bevy_transform(&self) -> Transform {
+        Transform::from_translation(self.translation.extend(self.layer))
+    }
+
+    /// Convert `visible` to Bevy's Visibility
+    fn bevy_visibility(&self) -> Visibility {
+        if self.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    }
+
+    /// Start loading the image of each kind of tile
+    fn load_images(&self, asset_server: &AssetServer) -> Vec<Handle<Image>> {
+        self.tiles
+            .iter()
+            .map(|tile| asset_server.load(tile.filepath.clone()))
+            .collect()
+    }
+
+    /// Spawn a child entity for each kind of tile, with a single mesh holding a quad for every
+    /// cell that tile is in, relative to the center of the tilemap. Tiles that show part of an
+    /// image need the size of the image, so this should wait until the images are loaded.
+    fn spawn_tiles(
+        &self,
+        commands: &mut Commands,
+        images: &[Handle<Image>],
+        image_assets: &Assets<Image>,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<ColorMaterial>,
+        parent: Entity,
+    ) {
+        let half = self.tile_size * 0.5;
+        let corners = [
+            Vec2::new(-half.x, half.y),
+            half,
+            Vec2::new(half.x, -half.y),
+            -half,
+        ];
+        for (index, (tile, image)) in self.tiles.iter().zip(images).enumerate() {
+            // The corners of the part of the image to show, in texture coordinates
+            let (uv_min, uv_max) = match (tile.rect, image_assets.get(image)) {
+                (Some(rect), Some(loaded)) => {
+                    let size = loaded.size_f32();
+                    (rect.min / size, rect.max / size)
+                }
+                _ => (Vec2::ZERO, Vec2::ONE),
+            };
+            // Flipping swaps which side of the image is on which side of the cell
+            let (uv_min, uv_max) = (
+                Vec2::new(
+                    if tile.flip_x { uv_max.x } else { uv_min.x },
+                    if tile.flip_y { uv_max.y } else { uv_min.y },
+                ),
+                Vec2::new(
+                    if tile.flip_x { uv_min.x } else { uv_max.x },
+                    if tile.flip_y { uv_min.y } else { uv_max.y },
+                ),
+            );
+            let uvs = [
+                uv_min,
+                Vec2::new(uv_max.x, uv_min.y),
+                uv_max,
+                Vec2::new(uv_min.x, uv_max.y),
+            ];
+
+            let mut positions = Vec::new();
+            let mut tex_coords = Vec::new();
+            let mut indices = Vec::new();
+            for (row, cells) in self.grid.iter().enumerate() {
+                for (column, cell_index) in cells.iter().enumerate() {
+                    if *cell_index != Some(index) {
+                        continue;
+                    }
+                    let cell = UVec2::new(column as u32, row as u32);
+                    let center = self.cell_to_world(cell) - self.translation;
+                    let first = positions.len() as u32;
+                    positions
+                        .extend(corners.map(|corner| (center + corner).extend(0.0).to_array()));
+                    tex_coords.extend(uvs.map(|uv| uv.to_array()));
+                    indices.extend([0, 3, 2, 0, 2, 1].map(|i| first + i));
+                }
+            }
+            if positions.is_empty() {
+                continue;
+            }
+
+            let mut mesh = Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            );
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_NORMAL,
+                vec![[0.0, 0.0, 1.0]; positions.len()],
+            );
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, tex_coords);
+            mesh.insert_indices(Indices::U32(indices));
+            let material = ColorMaterial {
+                texture: Some(image.clone()),
+                ..Default::default()
+            };
+            commands.entity(parent).with_child((
+                Mesh2d(meshes.add(mesh)),
+                MeshMaterial2d(materials.add(material)),
+                Transform::default(),
+            ));
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// The entity that draws a tilemap
+#[derive(Component)]
+struct SyncedTilemap {
+    label: String,
+    /// The image of each kind of tile, kept here so they stay loaded
+    images: Vec<Handle<Image>>,
+    /// Whether the tiles need to be respawned once the images are ready
+    respawn: bool,
+}
+
+impl SyncedTilemap {
+    /// Whether the images are ready to build tiles from. Failed images are treated as ready, so
+    /// that the rest of the tiles still show up.
+    fn images_ready(&self, asset_server: &AssetServer, image_assets: &Assets<Image>) -> bool {
+        self.images
+            .iter()
+            .all(|image| image_assets.contains(image) || asset_server.load_state(image).is_failed())
+    }
+}
+
+/// system - spawn, update, and despawn the entities for the tilemaps in the engine. The transform
+/// and visibility are compared with the entity's own components, and the tiles are only respawned
+/// after the tiles, grid, or tile size of a tilemap change. Headless games have no meshes, so
+/// there are no tiles to draw.
+#[allow(clippy::too_many_arguments)]
+fn sync_tilemaps(
+    mut commands: Commands,
+    mut engine: ResMut<Engine>,
+    asset_server: Res<AssetServer>,
+    image_assets: Res<Assets<Image>>,
+    mut meshes: Option<ResMut<Assets<Mesh>>>,
+    mut materials: Option<ResMut<Assets<ColorMaterial>>>,
+    mut query: Query<(Entity, &mut SyncedTilemap, &mut Transform, &mut Visibility)>,
+) {
+    let mut existing = HashSet::new();
+    for (entity, mut synced, mut transform, mut visibility) in query.iter_mut() {
+        let Some(tilemap) = engine.tilemaps.get_mut(&synced.label) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        existing.insert(tilemap.label.clone());
+        let new_transform = tilemap.bevy_transform();
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
+        let new_visibility = tilemap.bevy_visibility();
+        if *visibility != new_visibility {
+            *visibility = new_visibility;
+        }
+        if tilemap.changed {
+            tilemap.changed = false;
+            synced.images = tilemap.load_images(&asset_server);
+            synced.respawn = true;
+        }
+        if !synced.respawn || !synced.images_ready(&asset_server, &image_assets) {
+            continue;
+        }
+        let (Some(meshes), Some(materials)) = (meshes.as_deref_mut(), materials.as_deref_mut())
+        else {
+            continue;
+        };
+        synced.respawn = false;
+        commands.entity(entity).despawn_related::<Children>();
+        tilemap.spawn_tiles(
+            &mut commands,
+            &synced.images,
+            &image_assets,
+            meshes,
+            materials,
+            entity,
+        );
+    }
+    // New tilemaps get their tiles in a later frame, once their images are ready
+    for tilemap in engine.tilemaps.values_mut() {
+        if existing.contains(&tilemap.label) {
+            continue;
+        }
+        tilemap.changed = false;
+        commands.spawn((
+            SyncedTilemap {
+                label: tilemap.label.clone(),
+                images: tilemap.load_images(&asset_server),
+                respawn: true,
+            },
+            tilemap.bevy_transform(),
+            tilemap.bevy_visibility(),
+        ));
+    }
+}
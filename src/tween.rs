@@ -0,0 +1,511 @@
+//! Facilities for smoothly changing a sprite's or text's placement or color over time, known as
+//! tweening. A [`Tween`] goes from one value to another over a number of seconds, following an
+//! [`Easing`] curve, and can be chained into a sequence, delayed, repeated, and played back and
+//! forth.
+//!
+//! ```rust,no_run
+//! # use rusty_engine::prelude::*;
+//! #
+//! # #[derive(Resource)]
+//! # struct GameState;
+//! #
+//! # fn main() {
+//! let mut game = Game::new();
+//! let _ = game.add_sprite("car", SpritePreset::RacingCarRed);
+//! // Slide the car over to the right, then spin it around, then fade it out
+//! let handle = game.add_tween(
+//!     Tween::sprite("car", TweenValue::Translation(Vec2::new(300.0, 0.0)), 1.0)
+//!         .with_easing(Easing::QuadOut)
+//!         .then(Tween::sprite("car", TweenValue::Rotation(std::f32::consts::TAU), 0.5))
+//!         .then(Tween::sprite("car", TweenValue::Opacity(0.0), 0.5).with_delay(1.0)),
+//! );
+//! game.run(GameState);
+//! # }
+//! ```
+//!
+//! When a tween finishes, a [`TweenEvent`] with its handle shows up in
+//! [`Engine::tween_events`](crate::prelude::Engine::tween_events). Pass the handle to
+//! [`Engine::cancel_tween`](crate::prelude::Engine::cancel_tween) to stop a tween early.
+
+use crate::prelude::Engine;
+use bevy::prelude::*;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+/// A curve describing how a [`Tween`] speeds up and slows down. `In` curves start slowly, `Out`
+/// curves end slowly, and `InOut` curves do both. See <https://easings.net> for pictures of each
+/// curve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    /// The same speed all the way through
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    /// Pulls back a little before going forward
+    BackIn,
+    /// Overshoots a little before settling
+    BackOut,
+    BackInOut,
+    /// Wobbles past the end like a spring before settling
+    ElasticOut,
+    /// Bounces off the end like a dropped ball
+    BounceOut,
+}
+
+impl Easing {
+    /// How far along the curve is (usually `0.0` to `1.0`, though some curves overshoot) when
+    /// `t` of the time (`0.0` to `1.0`) has passed
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        // Constants from Robert Penner's easing equations
+        const BACK: f32 = 1.70158;
+        const BACK_IN_OUT: f32 = BACK * 1.525;
+        let in_out = |ease_in: fn(f32) -> f32| {
+            if t < 0.5 {
+                ease_in(t * 2.0) / 2.0
+            } else {
+                1.0 - ease_in((1.0 - t) * 2.0) / 2.0
+            }
+        };
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => in_out(|t| t * t),
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => in_out(|t| t * t * t),
+            Easing::SineIn => 1.0 - (t * FRAC_PI_2).cos(),
+            Easing::SineOut => (t * FRAC_PI_2).sin(),
+            Easing::SineInOut => (1.0 - (t * PI).cos()) / 2.0,
+            Easing::ExpoIn => expo_in(t),
+            Easing::ExpoOut => 1.0 - expo_in(1.0 - t),
+            Easing::ExpoInOut => in_out(expo_in),
+            Easing::BackIn => t * t * ((BACK + 1.0) * t - BACK),
+            Easing::BackOut => {
+                let t = 1.0 - t;
+                1.0 - t * t * ((BACK + 1.0) * t - BACK)
+            }
+            Easing::BackInOut => in_out(|t| t * t * ((BACK_IN_OUT + 1.0) * t - BACK_IN_OUT)),
+            Easing::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (TAU / 3.0)).sin() + 1.0
+                }
+            }
+            Easing::BounceOut => bounce_out(t),
+        }
+    }
+}
+
+fn expo_in(t: f32) -> f32 {
+    if t == 0.0 {
+        0.0
+    } else {
+        2f32.powf(10.0 * t - 10.0)
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// The sprite or text that a [`Tween`] changes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TweenTarget {
+    /// The sprite with this label
+    Sprite(String),
+    /// The text with this label
+    Text(String),
+}
+
+/// Which field a [`Tween`] changes, along with the value to change it to (or from)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TweenValue {
+    Translation(Vec2),
+    Rotation(f32),
+    /// The same scale along both axes. On a sprite, this also clears `scale_xy` (which would
+    /// otherwise take precedence over `scale`), so use [`TweenValue::ScaleXY`] for sprites that are
+    /// stretched.
+    Scale(f32),
+    /// Sprites only: a different scale along each axis, which sets `scale_xy`
+    ScaleXY(Vec2),
+    Layer(f32),
+    /// Sprites only: the color
+    Color(Color),
+    /// Sprites only: the opacity
+    Opacity(f32),
+}
+
+impl TweenValue {
+    /// The value `amount` of the way from `self` to `other`. If they are for different fields,
+    /// the result is `other`.
+    fn lerp(&self, other: &TweenValue, amount: f32) -> TweenValue {
+        match (self, other) {
+            (TweenValue::Translation(a), TweenValue::Translation(b)) => {
+                TweenValue::Translation(a.lerp(*b, amount))
+            }
+            (TweenValue::Rotation(a), TweenValue::Rotation(b)) => {
+                TweenValue::Rotation(a.lerp(*b, amount))
+            }
+            (TweenValue::Scale(a), TweenValue::Scale(b)) => TweenValue::Scale(a.lerp(*b, amount)),
+            (TweenValue::ScaleXY(a), TweenValue::ScaleXY(b)) => {
+                TweenValue::ScaleXY(a.lerp(*b, amount))
+            }
+            (TweenValue::Layer(a), TweenValue::Layer(b)) => TweenValue::Layer(a.lerp(*b, amount)),
+            (TweenValue::Color(a), TweenValue::Color(b)) => TweenValue::Color(a.mix(b, amount)),
+            (TweenValue::Opacity(a), TweenValue::Opacity(b)) => {
+                TweenValue::Opacity(a.lerp(*b, amount))
+            }
+            _ => *other,
+        }
+    }
+
+    /// Whether `self` and `other` are for the same field
+    fn same_field(&self, other: &TweenValue) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// Whether this is for a field that only sprites have
+    fn sprite_only(&self) -> bool {
+        matches!(
+            self,
+            TweenValue::ScaleXY(_) | TweenValue::Color(_) | TweenValue::Opacity(_)
+        )
+    }
+}
+
+/// What a [`Tween`] does when it reaches the end
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TweenRepeat {
+    /// Stop, and generate a [`TweenEvent`]
+    #[default]
+    Never,
+    /// Play this many more times, then stop and generate a [`TweenEvent`]
+    Times(u32),
+    /// Keep playing until cancelled
+    Forever,
+}
+
+/// One change in a [`Tween`]
+#[derive(Clone, Debug, PartialEq)]
+struct TweenStep {
+    target: TweenTarget,
+    from: Option<TweenValue>,
+    to: TweenValue,
+    duration: f32,
+    delay: f32,
+    easing: Easing,
+}
+
+/// A [`Tween`] smoothly changes a field of a sprite or text over time. Create one with
+/// [`Tween::sprite`] or [`Tween::text`], set it up with the builder methods, and then start it
+/// with [`Engine::add_tween`](crate::prelude::Engine::add_tween). Tweens are applied each frame
+/// before the logic functions run, so a logic function can still override the value.
+///
+/// The builder methods [`with_start`](Tween::with_start), [`with_easing`](Tween::with_easing), and
+/// [`with_delay`](Tween::with_delay) apply to the last change added, so that each change in a
+/// sequence made with [`then`](Tween::then) can have its own.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tween {
+    steps: Vec<TweenStep>,
+    repeat: TweenRepeat,
+    yoyo: bool,
+}
+
+impl Tween {
+    /// Change a field of the sprite with the given label to `to` over `seconds` seconds, starting
+    /// from whatever the field is when the tween starts
+    pub fn sprite<T: Into<String>>(label: T, to: TweenValue, seconds: f32) -> Self {
+        Self::new(TweenTarget::Sprite(label.into()), to, seconds)
+    }
+
+    /// Change a field of the text with the given label to `to` over `seconds` seconds, starting
+    /// from whatever the field is when the tween starts. Texts don't have a color, opacity, or
+    /// separate x and y scales, so [`TweenValue::Color`], [`TweenValue::Opacity`], and
+    /// [`TweenValue::ScaleXY`] are rejected with a warning: the tween just waits for `seconds`
+    /// seconds without changing anything.
+    pub fn text<T: Into<String>>(label: T, to: TweenValue, seconds: f32) -> Self {
+        Self::new(TweenTarget::Text(label.into()), to, seconds)
+    }
+
+    /// Change a field of `target` to `to` over `seconds` seconds
+    pub fn new(target: TweenTarget, to: TweenValue, seconds: f32) -> Self {
+        if let TweenTarget::Text(label) = &target
+            && to.sprite_only()
+        {
+            warn!("Ignoring tween of text {label:?} to {to:?}, which only sprites can do");
+        }
+        Self {
+            steps: vec![TweenStep {
+                target,
+                from: None,
+                to,
+                duration: seconds.max(0.0),
+                delay: 0.0,
+                easing: Easing::default(),
+            }],
+            repeat: TweenRepeat::default(),
+            yoyo: false,
+        }
+    }
+
+    /// Start from this value instead of the field's current value. Ignored if it's for a
+    /// different field than the tween changes.
+    pub fn with_start(mut self, start: TweenValue) -> Self {
+        if let Some(step) = self.steps.last_mut()
+            && step.to.same_field(&start)
+        {
+            step.from = Some(start);
+        }
+        self
+    }
+
+    /// Follow this easing curve. The default is [`Easing::Linear`].
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        if let Some(step) = self.steps.last_mut() {
+            step.easing = easing;
+        }
+        self
+    }
+
+    /// Wait this many seconds before starting
+    pub fn with_delay(mut self, seconds: f32) -> Self {
+        if let Some(step) = self.steps.last_mut() {
+            step.delay = seconds.max(0.0);
+        }
+        self
+    }
+
+    /// Once this tween is done, play `next`. `next` can change a different field, or even a
+    /// different sprite or text. Its repeat and yoyo settings are ignored -- set those on the
+    /// whole sequence instead.
+    pub fn then(mut self, next: Tween) -> Self {
+        self.steps.extend(next.steps);
+        self
+    }
+
+    /// What to do at the end. The default is [`TweenRepeat::Never`].
+    pub fn with_repeat(mut self, repeat: TweenRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// If `true`, each repeat plays backwards from the end to the start, and then forwards again,
+    /// and so on, instead of jumping back to the start. Playing backwards counts as a repeat, so
+    /// use [`TweenRepeat::Times(1)`](TweenRepeat::Times) to go there and back once.
+    pub fn with_yoyo(mut self, yoyo: bool) -> Self {
+        self.yoyo = yoyo;
+        self
+    }
+}
+
+/// Identifies a tween that was started with
+/// [`Engine::add_tween`](crate::prelude::Engine::add_tween), so that it can be cancelled, or
+/// recognized when it finishes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TweenHandle(pub(crate) u64);
+
+/// A [`TweenEvent`] is generated when a tween finishes all of its changes and repeats. Tweens that
+/// are cancelled, or whose sprite or text is removed, don't generate an event. See
+/// [`Engine::tween_events`](crate::prelude::Engine::tween_events).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TweenEvent {
+    /// The handle returned by [`Engine::add_tween`](crate::prelude::Engine::add_tween)
+    pub handle: TweenHandle,
+}
+
+/// A tween that is playing, along with how far along it is
+#[derive(Debug)]
+pub(crate) struct ActiveTween {
+    pub(crate) handle: TweenHandle,
+    tween: Tween,
+    // the step that is playing
+    step: usize,
+    // how long the step has been playing, including its delay
+    elapsed: f32,
+    // whether a yoyo tween is playing backwards
+    reverse: bool,
+    // how many more times to play, if not forever
+    plays_left: Option<u32>,
+}
+
+impl ActiveTween {
+    pub(crate) fn new(handle: TweenHandle, tween: Tween) -> Self {
+        let plays_left = match tween.repeat {
+            TweenRepeat::Never => Some(0),
+            TweenRepeat::Times(times) => Some(times),
+            TweenRepeat::Forever => None,
+        };
+        Self {
+            handle,
+            tween,
+            step: 0,
+            elapsed: 0.0,
+            reverse: false,
+            plays_left,
+        }
+    }
+}
+
+/// Advance all the tweens by `engine.delta_f32`, applying their values and generating events for
+/// any that finish. Called each frame before the logic functions run.
+pub(crate) fn advance_tweens(engine: &mut Engine) {
+    engine.tween_events.clear();
+    let delta = engine.delta_f32;
+    let mut tweens = std::mem::take(&mut engine.tweens);
+    tweens.retain_mut(|active| {
+        if active.tween.steps.is_empty() {
+            return false;
+        }
+        active.elapsed += delta;
+        // Each step can finish at most once per frame, so zero-length tweens can't loop forever
+        for _ in 0..active.tween.steps.len() {
+            let index = if active.reverse {
+                active.tween.steps.len() - 1 - active.step
+            } else {
+                active.step
+            };
+            let step = &mut active.tween.steps[index];
+            if active.elapsed < step.delay {
+                return true;
+            }
+            // Start from the field's current value, the first time the step plays
+            if step.from.is_none() {
+                step.from = read(engine, &step.target, &step.to);
+            }
+            let Some(from) = step.from else {
+                // The sprite or text is gone
+                return false;
+            };
+            let progress = if step.duration > 0.0 {
+                ((active.elapsed - step.delay) / step.duration).min(1.0)
+            } else {
+                1.0
+            };
+            let amount = if active.reverse {
+                step.easing.apply(1.0 - progress)
+            } else {
+                step.easing.apply(progress)
+            };
+            if !write(engine, &step.target, from.lerp(&step.to, amount)) {
+                return false;
+            }
+            if progress < 1.0 {
+                return true;
+            }
+            // On to the next step, keeping any leftover time
+            active.elapsed -= step.delay + step.duration;
+            active.step += 1;
+            if active.step < active.tween.steps.len() {
+                continue;
+            }
+            // That was the last step
+            match &mut active.plays_left {
+                Some(0) => {
+                    engine.tween_events.push(TweenEvent {
+                        handle: active.handle,
+                    });
+                    return false;
+                }
+                Some(plays_left) => *plays_left -= 1,
+                None => {}
+            }
+            active.step = 0;
+            if active.tween.yoyo {
+                active.reverse = !active.reverse;
+            }
+            return true;
+        }
+        true
+    });
+    engine.tweens = tweens;
+}
+
+/// The current value of the field of `target` that `field` is for
+fn read(engine: &Engine, target: &TweenTarget, field: &TweenValue) -> Option<TweenValue> {
+    match target {
+        TweenTarget::Sprite(label) => {
+            let sprite = engine.sprites.get(label)?;
+            Some(match field {
+                TweenValue::Translation(_) => TweenValue::Translation(sprite.translation),
+                TweenValue::Rotation(_) => TweenValue::Rotation(sprite.rotation),
+                TweenValue::Scale(_) => TweenValue::Scale(sprite.scale),
+                TweenValue::ScaleXY(_) => TweenValue::ScaleXY(sprite.scale_2d()),
+                TweenValue::Layer(_) => TweenValue::Layer(sprite.layer),
+                TweenValue::Color(_) => TweenValue::Color(sprite.color),
+                TweenValue::Opacity(_) => TweenValue::Opacity(sprite.opacity),
+            })
+        }
+        TweenTarget::Text(label) => {
+            let text = engine.texts.get(label)?;
+            Some(match field {
+                TweenValue::Translation(_) => TweenValue::Translation(text.translation),
+                TweenValue::Rotation(_) => TweenValue::Rotation(text.rotation),
+                TweenValue::Scale(_) => TweenValue::Scale(text.scale),
+                TweenValue::Layer(_) => TweenValue::Layer(text.layer),
+                TweenValue::ScaleXY(_) | TweenValue::Color(_) | TweenValue::Opacity(_) => *field,
+            })
+        }
+    }
+}
+
+/// Set a field of `target`. Returns `false` if the target doesn't exist.
+fn write(engine: &mut Engine, target: &TweenTarget, value: TweenValue) -> bool {
+    match target {
+        TweenTarget::Sprite(label) => {
+            let Some(sprite) = engine.sprites.get_mut(label) else {
+                return false;
+            };
+            match value {
+                TweenValue::Translation(translation) => sprite.translation = translation,
+                TweenValue::Rotation(rotation) => sprite.rotation = rotation,
+                TweenValue::Scale(scale) => {
+                    sprite.scale = scale;
+                    sprite.scale_xy = None;
+                }
+                TweenValue::ScaleXY(scale) => sprite.scale_xy = Some(scale),
+                TweenValue::Layer(layer) => sprite.layer = layer,
+                TweenValue::Color(color) => sprite.color = color,
+                TweenValue::Opacity(opacity) => sprite.opacity = opacity,
+            }
+        }
+        TweenTarget::Text(label) => {
+            let Some(text) = engine.texts.get_mut(label) else {
+                return false;
+            };
+            match value {
+                TweenValue::Translation(translation) => text.translation = translation,
+                TweenValue::Rotation(rotation) => text.rotation = rotation,
+                TweenValue::Scale(scale) => text.scale = scale,
+                TweenValue::Layer(layer) => text.layer = layer,
+                TweenValue::ScaleXY(_) | TweenValue::Color(_) | TweenValue::Opacity(_) => {}
+            }
+        }
+    }
+    true
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    frames: Vec<usize>,
+    events: Vec<AnimationEvent>,
+}
+
+fn watch(engine: &mut Engine, game_state: &mut GameState) {
+    game_state
+        .frames
+        .push(engine.sprites["hero"].animation_frame);
+    game_state.events.append(&mut engine.animation_events);
+}
+
+/// Play `animation` on a sprite for `frames` frames of 100ms (the default frame duration), and
+/// return the animation frame it was on each frame, along with the animation events
+fn play(animation: Animation, frames: u32) -> GameState {
+    let mut game = Game::new();
+    game.add_animation("walk", animation);
+    game.add_sprite("hero", SpritePreset::RacingCarRed)
+        .play_animation("walk");
+    game.add_logic(watch);
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(frames);
+    let (_, game_state) = headless.finish();
+    game_state
+}
+
+fn four_frames() -> Animation {
+    Animation::from_images(["walk/0.png", "walk/1.png", "walk/2.png", "walk/3.png"])
+}
+
+#[test]
+fn looping_animations_start_over() {
+    let game_state = play(four_frames(), 6);
+    assert_eq!(game_state.frames, [1, 2, 3, 0, 1, 2]);
+    assert!(game_state.events.is_empty());
+}
+
+#[test]
+fn ping_pong_animations_turn_around() {
+    let game_state = play(four_frames().with_mode(AnimationMode::PingPong), 8);
+    assert_eq!(game_state.frames, [1, 2, 3, 2, 1, 0, 1, 2]);
+}
+
+#[test]
+fn one_shot_animations_stop_on_the_last_frame() {
+    let game_state = play(four_frames().with_mode(AnimationMode::OneShot), 6);
+    assert_eq!(game_state.frames, [1, 2, 3, 3, 3, 3]);
+    // The event comes once the last frame has been shown for its whole duration
+    assert_eq!(
+        game_state.events,
+        [AnimationEvent {
+            sprite_label: "hero".into(),
+            animation: "walk".into(),
+        }]
+    );
+}
+
+#[test]
+fn sprite_sheet_animations_use_their_indices() {
+    let sheet = Animation::from_sprite_sheet("sheet.png", UVec2::new(16, 16), 4, 2)
+        .with_sheet_indices([4, 5, 6]);
+    assert_eq!(sheet.frame_count(), 3);
+    let game_state = play(sheet, 4);
+    assert_eq!(game_state.frames, [1, 2, 0, 1]);
+}
+
+#[test]
+fn zero_second_frames_are_skipped() {
+    let mut game = Game::new();
+    game.add_animation(
+        "blink",
+        four_frames().with_frame_durations([0.25, 0.0, 0.25, -1.0]),
+    );
+    game.add_sprite("hero", SpritePreset::RacingCarRed)
+        .play_animation("blink");
+    game.add_logic(watch);
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(250));
+    headless.step_frames(4);
+    // Frames 1 and 3 last zero seconds (negative counts as zero), so they never show
+    assert_eq!(headless.game_state().frames, [2, 0, 2, 0]);
+}
+
+#[test]
+fn animations_where_every_frame_is_zero_seconds_do_not_hang() {
+    let mut game = Game::new();
+    game.add_animation("spin", four_frames().with_frame_duration(0.0));
+    game.add_sprite("hero", SpritePreset::RacingCarRed)
+        .play_animation("spin");
+    game.add_logic(watch);
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(3);
+    assert_eq!(headless.game_state().frames.len(), 3);
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    events: Vec<CollisionEvent>,
+}
+
+fn collect_events(engine: &mut Engine, game_state: &mut GameState) {
+    game_state.events.append(&mut engine.collision_events);
+}
+
+#[test]
+fn sprite_and_shape_with_the_same_label_collide() {
+    let mut game = Game::new();
+    let car = game.add_sprite("car", SpritePreset::RacingCarRed);
+    car.collision = true;
+    let shape = game.add_shape("car", ShapeKind::Rectangle(Vec2::new(50.0, 50.0)));
+    shape.collision = true;
+    game.add_logic(collect_events);
+
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(3);
+
+    let events = &headless.game_state().events;
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert!(event.state.is_begin());
+    assert_eq!(event.pair, CollisionPair("car".into(), "car".into()));
+    // One side is the sprite, and the other is the shape
+    assert!(event.shapes.0 != event.shapes.1);
+}
+
+#[test]
+fn concave_shapes_collide_as_their_convex_hull() {
+    let l_shape = ShapeKind::Polygon(vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(64.0, 0.0),
+        Vec2::new(64.0, 32.0),
+        Vec2::new(32.0, 32.0),
+        Vec2::new(32.0, 64.0),
+        Vec2::new(0.0, 64.0),
+    ]);
+    let collider = Shape::new("l", l_shape).collider();
+    assert!(collider.is_convex());
+    let Collider::Poly(points) = collider else {
+        panic!("the shape has no polygon collider");
+    };
+    assert_eq!(points.len(), 5);
+    assert!(!points.contains(&Vec2::new(32.0, 32.0)));
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    steps: u32,
+    steps_per_frame: Vec<u32>,
+    alphas: Vec<f32>,
+    positions: Vec<f32>,
+}
+
+fn fixed_step(_: &mut Engine, game_state: &mut GameState) {
+    game_state.steps += 1;
+}
+
+fn frame(engine: &mut Engine, game_state: &mut GameState) {
+    game_state
+        .steps_per_frame
+        .push(std::mem::take(&mut game_state.steps));
+    game_state.alphas.push(engine.fixed_alpha);
+}
+
+#[test]
+fn fixed_steps_follow_game_time() {
+    let mut game = Game::new();
+    // Four fixed steps for every ten 25ms frames
+    game.add_fixed_logic(16.0, fixed_step);
+    game.add_logic(frame);
+
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(25));
+    headless.step_frames(10);
+
+    let game_state = headless.game_state();
+    assert_eq!(game_state.steps_per_frame, [0, 0, 1, 0, 1, 0, 0, 1, 0, 1]);
+    // The alpha is how much of the next step has built up
+    let expected = [0.4, 0.8, 0.2, 0.6, 0.0, 0.4, 0.8, 0.2, 0.6, 0.0];
+    for (alpha, expected) in game_state.alphas.iter().zip(expected) {
+        assert!((alpha - expected).abs() < 1e-3, "{alpha} != {expected}");
+    }
+}
+
+#[test]
+fn slow_frames_catch_up_with_several_steps() {
+    let mut game = Game::new();
+    game.add_fixed_logic(100.0, fixed_step);
+    game.add_logic(frame);
+
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(50));
+    headless.step_frames(2);
+
+    assert_eq!(headless.game_state().steps_per_frame, [5, 5]);
+}
+
+#[test]
+fn fixed_rates_that_are_not_positive_numbers_become_sixty() {
+    for hz in [0.0, -5.0, f32::NAN, f32::INFINITY] {
+        let mut game = Game::new();
+        game.add_fixed_logic(hz, fixed_step);
+        game.add_logic(frame);
+
+        let mut headless = game.headless(GameState::default(), Duration::from_secs(1));
+        headless.step_frames(1);
+
+        // At most ten steps run per frame, so a second at 60 steps per second is cut short
+        assert_eq!(headless.game_state().steps_per_frame, [10], "hz {hz}");
+    }
+}
+
+#[test]
+fn fixed_logic_sees_sprites_move_each_step() {
+    let mut game = Game::new();
+    let car = game.add_sprite("car", SpritePreset::RacingCarRed);
+    car.velocity = Vec2::new(100.0, 0.0);
+    game.add_fixed_logic(10.0, |engine: &mut Engine, game_state: &mut GameState| {
+        game_state
+            .positions
+            .push(engine.sprites["car"].translation.x);
+    });
+
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(300));
+    headless.step_frames(1);
+
+    // Each step sees the car where the step before it left it, and the frame doesn't move it again
+    let positions = &headless.game_state().positions;
+    assert_eq!(positions.len(), 3);
+    for (position, expected) in positions.iter().zip([0.0, 10.0, 20.0]) {
+        assert!(
+            (position - expected).abs() < 1e-3,
+            "{position} != {expected}"
+        );
+    }
+    let x = headless.engine().sprites["car"].translation.x;
+    assert!((x - 30.0).abs() < 1e-3, "{x}");
+}
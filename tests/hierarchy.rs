@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    events: Vec<CollisionEvent>,
+}
+
+fn close(a: Vec2, b: Vec2) -> bool {
+    (a - b).length() < 1e-3
+}
+
+fn tank_game() -> Game<GameState> {
+    let mut game = Game::new();
+    let tank = game.add_sprite("tank", SpritePreset::RacingCarRed);
+    tank.translation = Vec2::new(100.0, 0.0);
+    tank.rotation = UP;
+    tank.scale = 2.0;
+    let turret = game.add_sprite("turret", SpritePreset::RacingBarrelRed);
+    turret.parent = Some("tank".into());
+    turret.translation = Vec2::new(10.0, 0.0);
+    let flag = game.add_sprite("flag", SpritePreset::RacingConeStraight);
+    flag.parent = Some("turret".into());
+    flag.translation = Vec2::new(5.0, 0.0);
+    flag.rotation = RIGHT;
+    flag.scale = 0.5;
+    let name_tag = game.add_text("name_tag", "Tank");
+    name_tag.parent = Some("tank".into());
+    name_tag.translation = Vec2::new(0.0, 30.0);
+    game
+}
+
+#[test]
+fn children_follow_their_parents() {
+    let mut headless = tank_game().headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(1);
+    let engine = headless.engine();
+
+    let turret = engine.sprite_world_transform("turret").unwrap();
+    assert!(close(turret.translation, Vec2::new(100.0, 20.0)));
+    assert!((turret.rotation - UP).abs() < 1e-5);
+    assert_eq!(turret.scale, Vec2::splat(2.0));
+
+    // Grandchildren go through both parents
+    let flag = engine.sprite_world_transform("flag").unwrap();
+    assert!(close(flag.translation, Vec2::new(100.0, 30.0)));
+    assert_eq!(flag.scale, Vec2::ONE);
+
+    // "Above" the tank is to its left, since it faces up
+    let name_tag = engine.text_world_transform("name_tag").unwrap();
+    assert!(close(name_tag.translation, Vec2::new(40.0, 0.0)));
+
+    assert!(engine.sprite_world_transform("nothing").is_none());
+}
+
+#[test]
+fn flipping_a_parent_mirrors_its_children() {
+    let mut game = tank_game();
+    game.sprites.get_mut("tank").unwrap().flip_x = true;
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(1);
+
+    let turret = headless.engine().sprite_world_transform("turret").unwrap();
+    assert!(close(turret.translation, Vec2::new(100.0, -20.0)));
+    assert_eq!(turret.scale, Vec2::new(-2.0, 2.0));
+}
+
+#[test]
+fn missing_and_looping_parents_do_not_hang() {
+    let mut game = Game::new();
+    let a = game.add_sprite("a", SpritePreset::RacingCarRed);
+    a.parent = Some("b".into());
+    a.translation = Vec2::new(1.0, 0.0);
+    let b = game.add_sprite("b", SpritePreset::RacingCarBlue);
+    b.parent = Some("a".into());
+    b.translation = Vec2::new(0.0, 1.0);
+    let orphan = game.add_sprite("orphan", SpritePreset::RacingCarGreen);
+    orphan.parent = Some("gone".into());
+    orphan.translation = Vec2::new(7.0, 8.0);
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(1);
+    let engine = headless.engine();
+
+    assert!(engine.sprite_world_transform("a").is_some());
+    // A missing parent is the world itself
+    let orphan = engine.sprite_world_transform("orphan").unwrap();
+    assert_eq!(orphan.translation, Vec2::new(7.0, 8.0));
+}
+
+#[test]
+fn children_collide_where_they_are_in_the_world() {
+    let mut game = Game::new();
+    let _ = game.add_sprite("car", SpritePreset::RacingCarRed);
+    let bumper = game.add_sprite("bumper", SpritePreset::RacingBarrelRed);
+    bumper.parent = Some("car".into());
+    bumper.translation = Vec2::new(300.0, 0.0);
+    bumper.collision = true;
+    let barrel = game.add_sprite("barrel", SpritePreset::RacingBarrelBlue);
+    barrel.translation = Vec2::new(300.0, 0.0);
+    barrel.collision = true;
+    game.add_logic(|engine: &mut Engine, game_state: &mut GameState| {
+        game_state.events.append(&mut engine.collision_events);
+    });
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(2);
+
+    let events = &headless.game_state().events;
+    assert_eq!(events.len(), 1);
+    assert!(events[0].pair.either_equals_to("bumper"));
+    assert!(events[0].pair.either_equals_to("barrel"));
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    frames: u32,
+    results: Vec<Result<LogicHandle, EngineError>>,
+}
+
+#[derive(Resource)]
+struct OtherState;
+
+fn count_frames(_: &mut Engine, game_state: &mut GameState) {
+    game_state.frames += 1;
+}
+
+#[test]
+fn logic_for_another_game_state_is_turned_away() {
+    let mut game = Game::new();
+    game.add_logic(|engine: &mut Engine, game_state: &mut GameState| {
+        if game_state.results.is_empty() {
+            game_state.results.push(engine.add_logic(count_frames));
+            game_state
+                .results
+                .push(engine.add_logic(|_: &mut Engine, _: &mut OtherState| {}));
+            game_state
+                .results
+                .push(engine.add_fixed_logic(60.0, |_: &mut Engine, _: &mut OtherState| {}));
+        }
+    });
+
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(3);
+
+    let game_state = headless.game_state();
+    assert!(game_state.results[0].is_ok());
+    for result in &game_state.results[1..] {
+        assert!(matches!(result, Err(EngineError::GameStateType { .. })));
+    }
+    // The logic function with the right type started running the frame after it was added
+    assert_eq!(game_state.frames, 2);
+}
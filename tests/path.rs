@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    positions: Vec<Vec2>,
+    // (frame, waypoint, finished)
+    events: Vec<(usize, usize, bool)>,
+}
+
+/// Records where the car is and the waypoints it reaches each frame
+fn record(engine: &mut Engine, game_state: &mut GameState) {
+    let frame = game_state.positions.len();
+    for event in &engine.path_events {
+        assert_eq!(event.sprite_label, "car");
+        game_state
+            .events
+            .push((frame, event.waypoint, event.finished));
+    }
+    let car = engine.sprites.get("car").unwrap();
+    game_state.positions.push(car.translation);
+}
+
+/// A game with a car following `path`
+fn game(path: SpritePath) -> Game<GameState> {
+    let mut game = Game::new();
+    game.add_sprite("car", SpritePreset::RacingCarBlue)
+        .follow_path(path);
+    game.add_logic(record);
+    game
+}
+
+#[test]
+fn waypoints_are_reached_in_order_until_the_end() {
+    let path = SpritePath::new(
+        PathKind::Lines,
+        vec![Vec2::ZERO, Vec2::new(100.0, 0.0), Vec2::new(100.0, 100.0)],
+        100.0,
+    );
+    assert_eq!(path.waypoint_count(), 3);
+    assert_eq!(path.length(), 200.0);
+    assert_eq!(path.position_at(150.0), Some(Vec2::new(100.0, 50.0)));
+
+    let mut headless = game(path).headless(GameState::default(), Duration::from_millis(500));
+    headless.step_frames(6);
+    let game_state = headless.game_state();
+    assert_eq!(
+        game_state.positions,
+        [
+            Vec2::new(50.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 50.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(100.0, 100.0),
+        ]
+    );
+    assert_eq!(game_state.events, [(1, 1, false), (3, 2, true)]);
+    let car = headless.engine().sprites.get("car").unwrap().clone();
+    assert!(car.path.unwrap().finished);
+}
+
+#[test]
+fn paused_and_finished_paths_leave_the_sprite_alone() {
+    let mut game = game(SpritePath::new(
+        PathKind::Lines,
+        vec![Vec2::ZERO, Vec2::new(100.0, 0.0)],
+        100.0,
+    ));
+    // Pause the path on the first frame and move the car by hand for a while, then let it finish
+    // the path and move it by hand again
+    game.add_logic(|engine: &mut Engine, _: &mut GameState| {
+        let car = engine.sprites.get_mut("car").unwrap();
+        let path = car.path.as_mut().unwrap();
+        if path.distance == 50.0 && car.translation.y == 0.0 {
+            path.paused = true;
+        }
+        if path.paused || path.finished {
+            car.translation.y -= 10.0;
+        }
+        if path.paused && car.translation.y <= -30.0 {
+            car.translation = Vec2::ZERO;
+            path.paused = false;
+        }
+    });
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(500));
+    headless.step_frames(6);
+    assert_eq!(
+        headless.game_state().positions,
+        [
+            Vec2::new(50.0, 0.0),
+            Vec2::new(50.0, -10.0),
+            Vec2::new(50.0, -20.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, -10.0),
+            Vec2::new(100.0, -20.0),
+        ]
+    );
+    let car = headless.engine().sprites.get("car").unwrap().clone();
+    assert!(car.path.unwrap().finished);
+}
+
+#[test]
+fn bezier_loops_start_over_at_the_first_waypoint() {
+    // A straight line from (0, 0) to (100, 0), evenly spaced so it is easy to follow
+    let mut path = SpritePath::new(
+        PathKind::Bezier,
+        vec![
+            Vec2::ZERO,
+            Vec2::new(25.0, 0.0),
+            Vec2::new(75.0, 0.0),
+            Vec2::new(100.0, 0.0),
+        ],
+        100.0,
+    );
+    path.mode = PathMode::Loop;
+    assert_eq!(path.waypoint_count(), 2);
+
+    let mut headless = game(path).headless(GameState::default(), Duration::from_millis(750));
+    headless.step_frames(2);
+    let game_state = headless.game_state();
+    assert_eq!(game_state.events, [(1, 1, false), (1, 0, false)]);
+    assert!((game_state.positions[1].x - 50.0).abs() < 1.0);
+}
+
+#[test]
+fn changing_the_points_changes_the_route() {
+    let mut game = game(SpritePath::new(
+        PathKind::Lines,
+        vec![Vec2::ZERO, Vec2::new(100.0, 0.0)],
+        100.0,
+    ));
+    // Once the car has started along the path, point it somewhere else
+    game.add_logic(|engine: &mut Engine, _: &mut GameState| {
+        let path = engine
+            .sprites
+            .get_mut("car")
+            .unwrap()
+            .path
+            .as_mut()
+            .unwrap();
+        path.points[1] = Vec2::new(0.0, 200.0);
+    });
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(250));
+    headless.step_frames(2);
+    assert_eq!(
+        headless.game_state().positions,
+        [Vec2::new(25.0, 0.0), Vec2::new(0.0, 50.0)]
+    );
+    let car = headless.engine().sprites.get("car").unwrap().clone();
+    assert_eq!(car.path.unwrap().length(), 200.0);
+}
@@ -0,0 +1,53 @@
+use rusty_engine::prelude::*;
+
+#[test]
+fn pixels_outside_the_image_have_no_color() {
+    let mut image = PixelImage::new(2, 3);
+    image.set_pixel(1, 2, Color::WHITE);
+    assert_eq!(image.pixel(1, 2), Some(Color::srgba_u8(255, 255, 255, 255)));
+    assert_eq!(image.pixel(0, 0), Some(Color::srgba_u8(0, 0, 0, 0)));
+    assert_eq!(image.pixel(2, 0), None);
+    assert_eq!(image.pixel(0, 3), None);
+    assert_eq!(image.pixel(u32::MAX, u32::MAX), None);
+    // Setting a pixel outside the image does nothing
+    image.set_pixel(5, 5, Color::WHITE);
+    assert_eq!(image.rgba().len(), 2 * 3 * 4);
+}
+
+#[test]
+fn images_can_be_resized_to_and_from_nothing() {
+    let mut image = PixelImage::new(3, 2);
+    image.fill(Color::WHITE);
+    image.set_pixel(0, 1, Color::BLACK);
+
+    image.resize(2, 3);
+    assert_eq!(image.size(), UVec2::new(2, 3));
+    assert_eq!(image.pixel(1, 0), Some(Color::srgba_u8(255, 255, 255, 255)));
+    assert_eq!(image.pixel(0, 1), Some(Color::srgba_u8(0, 0, 0, 255)));
+    assert_eq!(image.pixel(1, 2), Some(Color::srgba_u8(0, 0, 0, 0)));
+
+    image.resize(0, 3);
+    assert_eq!(image.size(), UVec2::new(0, 3));
+    assert!(image.rgba().is_empty());
+    assert_eq!(image.pixel(0, 0), None);
+
+    image.resize(2, 2);
+    assert_eq!(image.rgba(), [0; 16]);
+
+    let mut image = PixelImage::new(4, 0);
+    image.resize(0, 0);
+    image.resize(1, 1);
+    assert_eq!(image.pixel(0, 0), Some(Color::srgba_u8(0, 0, 0, 0)));
+}
+
+#[test]
+fn buffers_must_match_the_size() {
+    assert!(PixelImage::from_rgba(2, 2, vec![0; 16]).is_ok());
+    assert!(matches!(
+        PixelImage::from_rgba(2, 2, vec![0; 15]),
+        Err(EngineError::PixelImageSize {
+            expected: 16,
+            actual: 15
+        })
+    ));
+}
@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::{bevy::prelude::Entity, *};
+
+#[derive(Resource, Default)]
+struct GameState {
+    key_windows: Vec<Entity>,
+}
+
+fn drive(engine: &mut Engine, game_state: &mut GameState) {
+    let distance = 100.0 * engine.delta_f32;
+    let car = engine.sprites.get_mut("car").unwrap();
+    if engine.keyboard_state.pressed(KeyCode::ArrowRight) {
+        car.translation.x += distance;
+    }
+    if engine.keyboard_state.pressed(KeyCode::ArrowUp) {
+        car.translation.y += distance;
+    }
+    game_state
+        .key_windows
+        .extend(engine.keyboard_events.iter().map(|event| event.window));
+}
+
+fn new_game() -> Game<GameState> {
+    let mut game = Game::new();
+    let _ = game.add_sprite("car", SpritePreset::RacingCarRed);
+    game.add_logic(drive);
+    game
+}
+
+#[test]
+fn replaying_a_recording_repeats_the_game() {
+    let mut game = new_game();
+    game.start_recording();
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.simulated_input().press_key(KeyCode::ArrowRight);
+    headless.step_frames(3);
+    headless.simulated_input().press_key(KeyCode::ArrowUp);
+    headless.step_frames(2);
+    headless.simulated_input().release_key(KeyCode::ArrowRight);
+    headless.step_frames(2);
+    let (mut engine, _) = headless.finish();
+    let recorded = engine.sprites["car"].translation;
+    let mut recording = engine.stop_recording().unwrap();
+    assert_eq!(recording.frames.len(), 7);
+    assert!(recorded.x > 0.0 && recorded.y > 0.0);
+
+    // The recording survives a trip through a file
+    let filepath = std::env::temp_dir().join("rusty_engine_recording_test.ron");
+    recording.write_to_file(&filepath).unwrap();
+    let read = InputRecording::read_from_file(&filepath).unwrap();
+    let _ = std::fs::remove_file(&filepath);
+    assert_eq!(read.frames.len(), recording.frames.len());
+
+    // Pretend the recording was made with a window that doesn't exist anymore
+    for frame in recording.frames.iter_mut() {
+        for event in frame.keyboard_events.iter_mut() {
+            event.window = Entity::from_raw_u32(12345).unwrap();
+        }
+    }
+
+    // The replay uses the recorded delta and input, not its own
+    let mut game = new_game();
+    game.replay_input(recording);
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(30));
+    headless.simulated_input().press_key(KeyCode::ArrowUp);
+    headless.step_frames(7);
+    let engine = headless.engine();
+    assert_eq!(engine.sprites["car"].translation, recorded);
+
+    // Replayed events point at the current window, which is a placeholder when headless
+    let key_windows = &headless.game_state().key_windows;
+    assert_eq!(key_windows.len(), 3);
+    assert!(
+        key_windows
+            .iter()
+            .all(|&window| window == Entity::PLACEHOLDER)
+    );
+}
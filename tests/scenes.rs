@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    log: Vec<String>,
+    frame: u32,
+}
+
+fn menu_enter(engine: &mut Engine, game_state: &mut GameState) {
+    game_state.log.push("enter menu".into());
+    let _ = engine.add_sprite("title", SpritePreset::RacingBarrierRed);
+    let _ = engine.add_text("press start", "Press start");
+    engine
+        .add_sprite("cursor", SpritePreset::RacingConeStraight)
+        .persistent = true;
+}
+
+fn menu_exit(engine: &mut Engine, game_state: &mut GameState) {
+    // The scene's sprites are still around while it exits
+    assert!(engine.sprites.contains_key("title"));
+    game_state.log.push("exit menu".into());
+}
+
+fn menu_logic(engine: &mut Engine, game_state: &mut GameState) {
+    game_state.log.push("menu".into());
+    if game_state.frame == 2 {
+        engine.switch_scene("play");
+    }
+}
+
+fn play_enter(engine: &mut Engine, game_state: &mut GameState) {
+    game_state.log.push("enter play".into());
+    let _ = engine.add_sprite("car", SpritePreset::RacingCarRed);
+}
+
+fn play_logic(_: &mut Engine, game_state: &mut GameState) {
+    game_state.log.push("play".into());
+}
+
+fn count_frames(_: &mut Engine, game_state: &mut GameState) {
+    game_state.frame += 1;
+}
+
+#[test]
+fn switching_scenes_runs_their_logic_and_cleans_up() {
+    let mut game = Game::new();
+    game.add_scene(
+        "menu",
+        Scene::new()
+            .on_enter(menu_enter)
+            .on_exit(menu_exit)
+            .with_logic(menu_logic),
+    );
+    game.add_scene(
+        "play",
+        Scene::new().on_enter(play_enter).with_logic(play_logic),
+    );
+    let _ = game.add_sprite("background", SpritePreset::RollingBlockSquare);
+    game.add_logic_with_priority(count_frames, -1);
+    game.switch_scene("menu");
+
+    // The first scene is entered at the end of the first frame, and its logic runs from then on
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(4);
+
+    assert_eq!(
+        headless.game_state().log,
+        [
+            "enter menu",
+            "menu",
+            "exit menu",
+            "enter play",
+            "play",
+            "play"
+        ]
+    );
+    let engine = headless.engine();
+    assert_eq!(engine.current_scene.as_deref(), Some("play"));
+    // The menu's sprites and texts are gone, except for the persistent one
+    assert!(!engine.sprites.contains_key("title"));
+    assert!(!engine.texts.contains_key("press start"));
+    assert!(engine.sprites.contains_key("cursor"));
+    // Sprites from before any scene, and from the new scene, are still around
+    assert!(engine.sprites.contains_key("background"));
+    assert!(engine.sprites.contains_key("car"));
+}
+
+#[test]
+fn unknown_scenes_are_ignored() {
+    let mut game = Game::new();
+    game.add_scene("menu", Scene::new().with_logic(menu_logic));
+    game.add_logic_with_priority(count_frames, -1);
+    game.switch_scene("menu");
+    game.add_logic(|engine: &mut Engine, game_state: &mut GameState| {
+        if game_state.frame == 2 {
+            engine.switch_scene("nowhere");
+        }
+    });
+
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(3);
+
+    assert_eq!(headless.engine().current_scene.as_deref(), Some("menu"));
+}
@@ -0,0 +1,23 @@
+use rusty_engine::prelude::*;
+
+#[test]
+fn anchor_offset_is_estimated_from_the_collider_until_the_image_loads() {
+    let mut sprite = Sprite::new("block", SpritePreset::RollingBlockSquare);
+    sprite.collider = Collider::rect(Vec2::new(-40.0, 20.0), Vec2::new(30.0, -10.0));
+    assert_eq!(sprite.anchor_offset(), Vec2::ZERO);
+
+    // The image is estimated to be 80x40, the smallest that holds the collider
+    sprite.anchor = Anchor::BottomLeft;
+    assert_eq!(sprite.anchor_offset(), Vec2::new(-40.0, -20.0));
+    sprite.anchor = Anchor::Custom(Vec2::new(0.25, 1.0));
+    assert_eq!(sprite.anchor_offset(), Vec2::new(20.0, 40.0));
+
+    // Once the image has loaded, its size is used instead
+    sprite.image_size = Some(Vec2::new(100.0, 60.0));
+    assert_eq!(sprite.anchor_offset(), Vec2::new(25.0, 60.0));
+
+    // Without an image or a collider, there's nothing to go on
+    sprite.image_size = None;
+    sprite.collider = Collider::NoCollider;
+    assert_eq!(sprite.anchor_offset(), Vec2::ZERO);
+}
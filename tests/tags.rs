@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    frame: u32,
+    enemies: Vec<Vec<String>>,
+}
+
+fn sorted(mut labels: Vec<String>) -> Vec<String> {
+    labels.sort();
+    labels
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    game_state.frame += 1;
+    match game_state.frame {
+        // Tags set directly on a sprite that was just added count right away
+        1 => {
+            let sprite = engine.add_sprite("b", SpritePreset::RacingCarBlue);
+            sprite.tags.insert("enemy".into());
+        }
+        // Tags changed directly on an existing sprite count from the next frame
+        2 => {
+            engine.sprites.get_mut("a").unwrap().tags.clear();
+            engine
+                .sprites
+                .get_mut("c")
+                .unwrap()
+                .tags
+                .insert("enemy".into());
+        }
+        // Removed sprites are gone right away
+        4 => {
+            engine.sprites.remove("b");
+        }
+        // Tags changed with tag_sprite count right away
+        5 => {
+            engine.tag_sprite("a", "enemy");
+        }
+        _ => {}
+    }
+    let labels = engine.sprite_labels_with_tag("enemy");
+    game_state.enemies.push(sorted(labels));
+}
+
+#[test]
+fn tag_lookups_follow_sprite_changes() {
+    let mut game = Game::new();
+    let a = game.add_sprite("a", SpritePreset::RacingCarRed);
+    a.tags.insert("enemy".into());
+    let _ = game.add_sprite("c", SpritePreset::RacingCarGreen);
+    game.add_logic(logic);
+
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(6);
+
+    let enemies = &headless.game_state().enemies;
+    assert_eq!(enemies[0], ["a", "b"]);
+    assert_eq!(enemies[1], ["b"]);
+    assert_eq!(enemies[2], ["b", "c"]);
+    assert_eq!(enemies[3], ["c"]);
+    assert_eq!(enemies[4], ["a", "c"]);
+    assert_eq!(enemies[5], ["a", "c"]);
+}
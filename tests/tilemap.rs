@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    events: Vec<CollisionEvent>,
+}
+
+/// A 3x2 tilemap of 32 pixel tiles centered on (100, 0), with a solid wall along the top and an
+/// empty cell in the bottom right
+fn walls() -> Tilemap {
+    let mut tilemap = Tilemap::new("walls", Vec2::splat(32.0));
+    tilemap.translation = Vec2::new(100.0, 0.0);
+    tilemap.set_tiles(vec![
+        Tile::new(SpritePreset::RollingBlockSquare, true),
+        Tile::new(SpritePreset::RollingBlockSmall, false),
+    ]);
+    tilemap.set_grid(vec![
+        vec![Some(0), Some(0), Some(0)],
+        vec![Some(1), Some(1), None],
+    ]);
+    tilemap
+}
+
+#[test]
+fn points_convert_to_cells_and_back() {
+    let tilemap = walls();
+    assert_eq!(tilemap.dimensions(), UVec2::new(3, 2));
+    assert_eq!(tilemap.size(), Vec2::new(96.0, 64.0));
+
+    // The top left corner is at (52, 32)
+    assert_eq!(
+        tilemap.world_to_cell(Vec2::new(52.0, 32.0)),
+        Some(UVec2::ZERO)
+    );
+    assert_eq!(
+        tilemap.world_to_cell(Vec2::new(147.9, -31.9)),
+        Some(UVec2::new(2, 1))
+    );
+    assert_eq!(tilemap.world_to_cell(Vec2::new(148.0, 0.0)), None);
+    assert_eq!(tilemap.world_to_cell(Vec2::new(60.0, 32.1)), None);
+    assert_eq!(tilemap.world_to_cell(Vec2::new(51.9, 0.0)), None);
+
+    assert_eq!(tilemap.cell_to_world(UVec2::ZERO), Vec2::new(68.0, 16.0));
+    assert_eq!(
+        tilemap.cell_to_world(UVec2::new(2, 1)),
+        Vec2::new(132.0, -16.0)
+    );
+    for column in 0..3 {
+        for row in 0..2 {
+            let cell = UVec2::new(column, row);
+            assert_eq!(
+                tilemap.world_to_cell(tilemap.cell_to_world(cell)),
+                Some(cell)
+            );
+        }
+    }
+}
+
+#[test]
+fn cells_know_their_tiles() {
+    let mut tilemap = walls();
+    assert!(tilemap.is_solid(UVec2::new(1, 0)));
+    assert!(!tilemap.is_solid(UVec2::new(1, 1)));
+    assert_eq!(tilemap.index_at(UVec2::new(2, 1)), None);
+    assert!(tilemap.tile_at(UVec2::new(5, 5)).is_none());
+    assert!(tilemap.solid_at(Vec2::new(100.0, 16.0)));
+    assert!(!tilemap.solid_at(Vec2::new(100.0, -16.0)));
+
+    // Setting a tile outside the grid grows it
+    tilemap.set_tile(UVec2::new(4, 2), Some(0));
+    assert_eq!(tilemap.dimensions(), UVec2::new(5, 3));
+    assert!(tilemap.is_solid(UVec2::new(4, 2)));
+}
+
+#[test]
+fn tile_sizes_must_be_positive() {
+    let mut tilemap = Tilemap::new("bad", Vec2::new(0.0, -3.0));
+    assert_eq!(tilemap.tile_size(), Vec2::splat(64.0));
+    tilemap.set_tile_size(Vec2::new(16.0, f32::NAN));
+    assert_eq!(tilemap.tile_size(), Vec2::new(16.0, 64.0));
+    // Nothing is inside a tilemap without any cells, but finding out doesn't divide by zero
+    assert_eq!(tilemap.world_to_cell(Vec2::ZERO), None);
+
+    // Saved tilemaps are checked too
+    let saved = ron::to_string(&walls()).unwrap();
+    let saved = saved.replace("tile_size:(32.0,32.0)", "tile_size:(0.0,32.0)");
+    let tilemap: Tilemap = ron::from_str(&saved).unwrap();
+    assert_eq!(tilemap.tile_size(), Vec2::new(64.0, 32.0));
+}
+
+#[test]
+fn sprites_collide_with_solid_tiles_only() {
+    let mut game = Game::new();
+    game.add_tilemap("walls", Vec2::splat(32.0))
+        .clone_from(&walls());
+    // Over the solid top row
+    let high = game.add_sprite("high", SpritePreset::RacingBarrelRed);
+    high.translation = Vec2::new(68.0, 16.0);
+    high.scale = 0.2;
+    high.collision = true;
+    // Over the non-solid bottom row
+    let low = game.add_sprite("low", SpritePreset::RacingBarrelBlue);
+    low.translation = Vec2::new(68.0, -20.0);
+    low.scale = 0.2;
+    low.collision = true;
+    game.add_logic(|engine: &mut Engine, game_state: &mut GameState| {
+        game_state.events.append(&mut engine.collision_events);
+    });
+
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(2);
+
+    let events = &headless.game_state().events;
+    assert_eq!(events.len(), 1);
+    assert!(events[0].state.is_begin());
+    assert!(events[0].pair.either_equals_to("walls"));
+    assert!(events[0].pair.either_equals_to("high"));
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    frame: u32,
+    deltas: Vec<f32>,
+    real_deltas: Vec<f32>,
+    fixed_steps: Vec<u32>,
+    steps: u32,
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    game_state.frame += 1;
+    game_state.deltas.push(engine.delta_f32);
+    game_state.real_deltas.push(engine.real_delta_f32);
+    game_state
+        .fixed_steps
+        .push(std::mem::take(&mut game_state.steps));
+    match game_state.frame {
+        2 => engine.paused = true,
+        4 => engine.paused = false,
+        5 => engine.time_scale = 0.5,
+        _ => {}
+    }
+}
+
+fn close(a: &[f32], b: &[f32]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-4)
+}
+
+#[test]
+fn pausing_and_scaling_time() {
+    let mut game = Game::new();
+    let car = game.add_sprite("car", SpritePreset::RacingCarRed);
+    car.velocity = Vec2::new(10.0, 0.0);
+    game.add_logic(logic);
+    game.add_fixed_logic(10.0, |_: &mut Engine, game_state: &mut GameState| {
+        game_state.steps += 1;
+    });
+
+    let mut headless = game.headless(GameState::default(), Duration::from_millis(100));
+    headless.step_frames(7);
+
+    // Changes to `paused` and `time_scale` take effect on the next frame
+    let game_state = headless.game_state();
+    let deltas = [0.1, 0.1, 0.0, 0.0, 0.1, 0.05, 0.05];
+    assert!(
+        close(&game_state.deltas, &deltas),
+        "{:?}",
+        game_state.deltas
+    );
+    // Real time keeps going no matter what
+    assert!(close(&game_state.real_deltas, &[0.1; 7]));
+    // Fixed logic doesn't run while paused, and runs at half speed in half time
+    assert_eq!(game_state.fixed_steps, [1, 1, 0, 0, 1, 0, 1]);
+    // Sprites only move in the fixed steps, so they don't move while paused either
+    let x = headless.engine().sprites["car"].translation.x;
+    assert!((x - 4.0).abs() < 1e-3, "{x}");
+}
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use rusty_engine::prelude::*;
+
+#[derive(Resource, Default)]
+struct GameState {
+    tween: Option<Tween>,
+    handle: Option<TweenHandle>,
+    xs: Vec<f32>,
+    scales: Vec<Option<Vec2>>,
+    finished: Vec<usize>,
+}
+
+/// Starts `game_state.tween` on the first frame, and then records the car's x position and
+/// `scale_xy`, and which frames the tween finished on
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    if let Some(tween) = game_state.tween.take() {
+        game_state.handle = Some(engine.add_tween(tween));
+    }
+    let car = engine.sprites.get("car").unwrap();
+    game_state.xs.push(car.translation.x);
+    game_state.scales.push(car.scale_xy);
+    for event in &engine.tween_events {
+        assert_eq!(Some(event.handle), game_state.handle);
+        game_state.finished.push(game_state.xs.len() - 1);
+    }
+}
+
+/// Plays `tween` on a car at the origin for `frames` frames of 250 milliseconds
+fn play(tween: Tween, frames: u32) -> GameState {
+    let mut game = Game::new();
+    let _ = game.add_sprite("car", SpritePreset::RacingCarBlue);
+    game.add_logic(logic);
+    let game_state = GameState {
+        tween: Some(tween),
+        ..Default::default()
+    };
+    let mut headless = game.headless(game_state, Duration::from_millis(250));
+    headless.step_frames(frames);
+    headless.finish().1
+}
+
+#[test]
+fn easing_curves_start_at_zero_and_end_at_one() {
+    let easings = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::SineIn,
+        Easing::SineOut,
+        Easing::SineInOut,
+        Easing::ExpoIn,
+        Easing::ExpoOut,
+        Easing::ExpoInOut,
+        Easing::BackIn,
+        Easing::BackOut,
+        Easing::BackInOut,
+        Easing::ElasticOut,
+        Easing::BounceOut,
+    ];
+    for easing in easings {
+        assert!(easing.apply(0.0).abs() < 1e-3, "{easing:?} starts at 0");
+        assert!(
+            (easing.apply(1.0) - 1.0).abs() < 1e-3,
+            "{easing:?} ends at 1"
+        );
+        // Out of range progress is clamped
+        assert_eq!(easing.apply(-1.0), easing.apply(0.0));
+        assert_eq!(easing.apply(2.0), easing.apply(1.0));
+    }
+    assert_eq!(Easing::Linear.apply(0.5), 0.5);
+    assert_eq!(Easing::QuadIn.apply(0.5), 0.25);
+    assert_eq!(Easing::QuadOut.apply(0.5), 0.75);
+}
+
+#[test]
+fn linear_tweens_move_evenly_and_finish_once() {
+    let tween = Tween::sprite("car", TweenValue::Translation(Vec2::new(100.0, 0.0)), 1.0);
+    let game_state = play(tween, 7);
+    assert_eq!(game_state.xs, [0.0, 25.0, 50.0, 75.0, 100.0, 100.0, 100.0]);
+    assert_eq!(game_state.finished, [4]);
+}
+
+#[test]
+fn yoyo_tweens_come_back() {
+    let tween = Tween::sprite("car", TweenValue::Translation(Vec2::new(100.0, 0.0)), 1.0)
+        .with_yoyo(true)
+        .with_repeat(TweenRepeat::Times(1));
+    let game_state = play(tween, 10);
+    assert_eq!(
+        game_state.xs,
+        [0.0, 25.0, 50.0, 75.0, 100.0, 75.0, 50.0, 25.0, 0.0, 0.0]
+    );
+    assert_eq!(game_state.finished, [8]);
+}
+
+#[test]
+fn repeating_tweens_start_over() {
+    let tween = Tween::sprite("car", TweenValue::Translation(Vec2::new(100.0, 0.0)), 0.5)
+        .with_repeat(TweenRepeat::Times(2));
+    let game_state = play(tween, 8);
+    assert_eq!(
+        game_state.xs,
+        [0.0, 50.0, 100.0, 50.0, 100.0, 50.0, 100.0, 100.0]
+    );
+    assert_eq!(game_state.finished, [6]);
+}
+
+#[test]
+fn sprites_can_be_stretched_and_unstretched() {
+    let tween = Tween::sprite("car", TweenValue::ScaleXY(Vec2::new(3.0, 5.0)), 0.5)
+        .then(Tween::sprite("car", TweenValue::Scale(2.0), 0.5).with_delay(0.25));
+    let game_state = play(tween, 6);
+    assert_eq!(
+        game_state.scales,
+        [
+            None,
+            Some(Vec2::new(2.0, 3.0)),
+            Some(Vec2::new(3.0, 5.0)),
+            None,
+            None,
+            None
+        ]
+    );
+    assert_eq!(game_state.finished, [5]);
+}
+
+#[test]
+fn texts_ignore_sprite_only_tweens() {
+    let mut game = Game::new();
+    let _ = game.add_sprite("car", SpritePreset::RacingCarBlue);
+    let text = game.add_text("score", "0");
+    text.translation = Vec2::new(10.0, 20.0);
+    game.add_logic(logic);
+    let tween = Tween::text("score", TweenValue::ScaleXY(Vec2::new(3.0, 5.0)), 0.5)
+        .then(Tween::text("score", TweenValue::Opacity(0.0), 0.5))
+        .then(Tween::text("score", TweenValue::Scale(2.0), 0.5));
+    let game_state = GameState {
+        tween: Some(tween),
+        ..Default::default()
+    };
+    let mut headless = game.headless(game_state, Duration::from_millis(250));
+    headless.step_frames(4);
+    let text = headless.engine().texts.get("score").unwrap().clone();
+    assert_eq!(text.scale, 1.0);
+    assert_eq!(text.translation, Vec2::new(10.0, 20.0));
+
+    // The sprite-only changes take their time, and then the rest of the sequence plays
+    headless.step_frames(4);
+    assert_eq!(headless.engine().texts.get("score").unwrap().scale, 2.0);
+    assert_eq!(headless.game_state().finished, [6]);
+}